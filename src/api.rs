@@ -1,53 +1,181 @@
+use crate::colors::{LineColor, Rgb};
+use crate::config::{BoardMode, FetchConfig, StopConfig};
 use crate::departure::Departure;
+use crate::destination;
+use crate::ring::RingDirection;
+use crate::urgency::Urgency;
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::error::Error;
+use std::fmt;
 use tracing::{info, error, warn, instrument};
 
 // API response structures for VBB HAFAS API
+// /departures fills `departures`, /arrivals fills `arrivals`
 #[derive(Debug, Deserialize)]
 struct ApiResponse {
+    #[serde(default)]
     departures: Vec<ApiDeparture>,
+    #[serde(default)]
+    arrivals: Vec<ApiDeparture>,
 }
 
-// TODO: might want to parse more fields like platform, provenance, etc.
-
 #[derive(Debug, Deserialize)]
 struct ApiDeparture {
     line: Line,
     direction: Option<String>,  // Can be null in API response
+    #[serde(default)]
+    provenance: Option<String>,  // Origin, only on arrivals
     when: Option<String>,        // Can be null in API response
     #[serde(default)]
-    #[allow(dead_code)] // Reserved for future delay/disruption display
-    delay: Option<i32>,
+    delay: Option<i32>,  // Seconds, null without real-time data
+    #[serde(default, rename = "plannedWhen")]
+    planned_when: Option<String>,
+    #[serde(default)]
+    platform: Option<String>,
+    #[serde(default)]
+    cancelled: bool,
+    #[serde(default)]
+    stop: Option<ApiStop>,
+    #[serde(default)]
+    remarks: Vec<ApiRemark>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiStop {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiRemark {
+    #[serde(rename = "type")]
+    kind: String,  // "warning", "hint" or "status"
+    summary: Option<String>,
+    text: Option<String>,
+}
+
+impl ApiRemark {
+    // Only warnings (disruptions, construction work) are worth showing,
+    // hints are things like "bicycle conveyance"
+    fn warning_text(self) -> Option<String> {
+        if self.kind != "warning" {
+            return None;
+        }
+        self.summary.or(self.text).filter(|t| !t.trim().is_empty())
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct Line {
     name: String,
+    #[serde(default)]
+    color: Option<ApiLineColor>,  // Only present for some lines
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiLineColor {
+    fg: String,
+    bg: String,
+}
+
+impl ApiLineColor {
+    fn parse(&self) -> Option<LineColor> {
+        Some(LineColor::new(Rgb::from_hex(&self.bg)?, Rgb::from_hex(&self.fg)?))
+    }
+}
+
+/// What a fetch failed on, the `kind` label of the error metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ErrorKind {
+    /// Request failed or the API answered with an error status
+    Http,
+    /// Connection lost while reading the body
+    Read,
+    /// Body isn't the JSON we expect
+    Parse,
+    Other,
+}
+
+impl ErrorKind {
+    pub const ALL: [ErrorKind; 4] = [ErrorKind::Http, ErrorKind::Read, ErrorKind::Parse, ErrorKind::Other];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ErrorKind::Http => "http",
+            ErrorKind::Read => "read",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+/// Error of `fetch_board`, boxed like every other error but still telling its kind
+#[derive(Debug)]
+pub struct FetchError {
+    pub kind: ErrorKind,
+    message: String,
 }
 
+impl FetchError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+
+    /// Kind of any fetch error, Other for errors that aren't a FetchError
+    pub fn kind_of(error: &(dyn Error + 'static)) -> ErrorKind {
+        error.downcast_ref::<FetchError>().map_or(ErrorKind::Other, |e| e.kind)
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for FetchError {}
+
 // Fetch departures for a specific stop
 // stop_id: Station ID (e.g., "900120003" for S+U Warschauer Str.)
-#[instrument(skip(agent))]
-pub fn fetch_departures(agent: &ureq::Agent, stop_id: &str) -> Result<Vec<Departure>, Box<dyn Error>> {
-    const WARSCHAUER_STOP_ID: &str = "900120003";
-    
-    let url = if stop_id == WARSCHAUER_STOP_ID {
-        "https://v6.vbb.transport.rest/stops/900120003/departures?duration=15"
-    } else {
+pub fn fetch_departures(
+    agent: &ureq::Agent,
+    stop_id: &str,
+    fetch: &FetchConfig,
+) -> Result<Vec<Departure>, Box<dyn Error>> {
+    fetch_board(agent, stop_id, BoardMode::Departures, fetch)
+}
+
+// Fetch arrivals for a specific stop, the origin takes the place of the destination
+pub fn fetch_arrivals(
+    agent: &ureq::Agent,
+    stop_id: &str,
+    fetch: &FetchConfig,
+) -> Result<Vec<Departure>, Box<dyn Error>> {
+    fetch_board(agent, stop_id, BoardMode::Arrivals, fetch)
+}
+
+#[instrument(skip(agent, fetch))]
+fn fetch_board(
+    agent: &ureq::Agent,
+    stop_id: &str,
+    mode: BoardMode,
+    fetch: &FetchConfig,
+) -> Result<Vec<Departure>, Box<dyn Error>> {
+    if stop_id.is_empty() || !stop_id.chars().all(|c| c.is_ascii_digit()) {
         return Err(format!("Unsupported stop_id: {}", stop_id).into());
-    };
-    
-    info!("Fetching departures from API: {}", url);
+    }
+
+    let url = format!("https://v6.vbb.transport.rest/stops/{}/{}?duration=15", stop_id, mode.path());
+
+    info!("Fetching {} from API: {}", mode.path(), url);
     let start_time = std::time::Instant::now();
     
-    let response = match agent.get(url).call() {
+    let response = match agent.get(&url).call() {
         Ok(r) => r,
         Err(e) => {
             let elapsed = start_time.elapsed();
             error!(elapsed_ms = elapsed.as_millis(), "HTTP error: {}", e);
-            return Err(format!("HTTP error: {}", e).into());
+            return Err(FetchError::new(ErrorKind::Http, format!("HTTP error: {}", e)).into());
         }
     };
 
@@ -56,43 +184,75 @@ pub fn fetch_departures(agent: &ureq::Agent, stop_id: &str) -> Result<Vec<Depart
         Err(e) => {
             let elapsed = start_time.elapsed();
             error!(elapsed_ms = elapsed.as_millis(), "HTTP read error: {}", e);
-            return Err(format!("HTTP read error: {}", e).into());
+            return Err(FetchError::new(ErrorKind::Read, format!("HTTP read error: {}", e)).into());
         }
     };
     
-    let mut api_response: ApiResponse = match serde_json::from_str(&body) {
-        Ok(r) => r,
+    let departures = match parse_board(&body, stop_id, mode, fetch, Utc::now()) {
+        Ok(d) => d,
         Err(e) => {
             let elapsed = start_time.elapsed();
-            error!(elapsed_ms = elapsed.as_millis(), "JSON parse error: {}", e);
-            return Err(format!("JSON parse error: {}", e).into());
+            error!(elapsed_ms = elapsed.as_millis(), "{}", e);
+            return Err(e);
         }
     };
-    
     drop(body);
+
+    let elapsed = start_time.elapsed();
+    info!(
+        elapsed_ms = elapsed.as_millis(),
+        count = departures.len(),
+        "API request successful, received {} {}",
+        departures.len(),
+        mode.path()
+    );
+
+    Ok(departures)
+}
     
-    let now = Utc::now();
+/// Board entries from an API response, filtered, cleaned and sorted
+/// `now` is what the minutes are counted from
+pub fn parse_board(
+    body: &str,
+    stop_id: &str,
+    mode: BoardMode,
+    fetch: &FetchConfig,
+    now: DateTime<Utc>,
+) -> Result<Vec<Departure>, Box<dyn Error>> {
+    let mut api_response: ApiResponse = serde_json::from_str(body)
+        .map_err(|e| FetchError::new(ErrorKind::Parse, format!("JSON parse error: {}", e)))?;
+
     let mut departures = Vec::with_capacity(15);
 
     // Extract and immediately drop api_response to free memory
-    let departures_vec = std::mem::take(&mut api_response.departures);
+    let departures_vec = match mode {
+        BoardMode::Departures => std::mem::take(&mut api_response.departures),
+        BoardMode::Arrivals => std::mem::take(&mut api_response.arrivals),
+    };
     drop(api_response); // Explicitly free ApiResponse struct
     
     for api_dep in departures_vec {
         // Skip if missing required fields
-        let direction = match api_dep.direction {
+        // Arrivals show where the train comes from
+        let direction = match mode {
+            BoardMode::Departures => api_dep.direction,
+            BoardMode::Arrivals => api_dep.provenance,
+        };
+        let direction = match direction {
             Some(d) => d,
             None => continue,
         };
         
-        // Skip departures going TO Warschauer Str. (we're already here!)
-        // TODO: make this configurable for other stations
-        if direction.contains("Warschauer") {
+        // Skip departures going to this very stop (we're already here!)
+        // Arrivals from there are real trains coming in, keep them
+        let stop_name = api_dep.stop.as_ref().and_then(|s| s.name.as_deref());
+        if mode == BoardMode::Departures && stop_name.is_some_and(|stop| is_same_stop(&direction, stop)) {
             drop(direction);
             continue;
         }
         
-        let when = match api_dep.when {
+        // Cancelled departures have no real-time `when`, count to the planned time
+        let when = match api_dep.when.or_else(|| api_dep.planned_when.clone().filter(|_| api_dep.cancelled)) {
             Some(w) => w,
             None => {
                 drop(direction);
@@ -102,8 +262,10 @@ pub fn fetch_departures(agent: &ureq::Agent, stop_id: &str) -> Result<Vec<Depart
         
         let line_name = &api_dep.line.name;
         
+        let ring = RingDirection::for_line(line_name);
+
         // Filter out unwanted lines
-        // Keep only: S-Bahn (except Ringbahn), U-Bahn, Trams (M-lines)
+        // Keep only: S-Bahn (Ringbahn if enabled), U-Bahn, Trams (M-lines)
         if line_name.starts_with("RE") ||    // Regional Express
            line_name.starts_with("RB") ||    // RegionalBahn
            line_name.starts_with("IC") ||    // InterCity
@@ -111,8 +273,7 @@ pub fn fetch_departures(agent: &ureq::Agent, stop_id: &str) -> Result<Vec<Depart
            line_name.starts_with("EN") ||    // EuroNight
            line_name.starts_with("FEX") ||   // Flughafen Express
            line_name.starts_with("ICE") ||   // InterCity Express
-           line_name == "S41" ||             // Ringbahn clockwise
-           line_name == "S42" ||             // Ringbahn counter-clockwise
+           (ring.is_some() && !fetch.ringbahn) ||  // Ringbahn S41/S42
            line_name.chars().all(|c| c.is_numeric()) {  // Buses (pure numbers)
             drop(direction);
             drop(when);
@@ -126,20 +287,42 @@ pub fn fetch_departures(agent: &ureq::Agent, stop_id: &str) -> Result<Vec<Depart
             let minutes = diff.num_minutes();
 
             // Only include future departures (at least 1 minute away, max 15 minutes)
-            if minutes >= 1 && minutes <= 15 {
-                // Clean up destination name (optimized: single pass where possible)
-                let destination = clean_destination(&direction);
+            if (1..=15).contains(&minutes) {
+                // The Ring has no end station, the next interchange tells where it goes
+                // (or came from, looking backwards for arrivals)
+                let ring_via = match mode {
+                    BoardMode::Departures => ring,
+                    BoardMode::Arrivals => ring.map(RingDirection::reversed),
+                };
+                let via = ring_via.zip(api_dep.stop.as_ref().and_then(|s| s.name.as_deref()));
+                let destination = match via.and_then(|(ring, stop)| ring.via(stop)) {
+                    Some(via) => via.to_string(),
+                    None => destination::clean(&direction, &fetch.destinations, fetch.default_destinations),
+                };
                 
+                let line_color = api_dep.line.color.as_ref().and_then(ApiLineColor::parse);
                 let line_name = api_dep.line.name;
+                let warnings = api_dep.remarks.into_iter().filter_map(ApiRemark::warning_text).collect();
                 
                 drop(direction);
                 drop(when);
                 
-                departures.push(Departure::new(
-                    line_name,
-                    destination,
-                    minutes as u32,
-                ));
+                departures.push(
+                    Departure::new(line_name, destination, minutes as u32)
+                        .with_stop(stop_id)
+                        .with_color(line_color)
+                        .with_warnings(warnings)
+                        .with_delay(api_dep.delay.map(|seconds| seconds / 60))
+                        .with_platform(api_dep.platform)
+                        .with_cancelled(api_dep.cancelled)
+                        .with_ring(ring)
+                        .with_planned(
+                            api_dep
+                                .planned_when
+                                .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                                .map(|t| t.with_timezone(&Utc)),
+                        ),
+                );
             } else {
                 drop(direction);
                 drop(when);
@@ -156,69 +339,79 @@ pub fn fetch_departures(agent: &ureq::Agent, stop_id: &str) -> Result<Vec<Depart
     // Shrink to fit to free unused capacity immediately
     departures.shrink_to_fit();
 
-    let elapsed = start_time.elapsed();
-    info!(
-        elapsed_ms = elapsed.as_millis(),
-        count = departures.len(),
-        "API request successful, received {} departures",
-        departures.len()
-    );
-
     Ok(departures)
 }
-    
-// Optimized destination cleaning - single pass where possible
-fn clean_destination(dest: &str) -> String {
-    let mut result = String::with_capacity(dest.len());
-    let mut chars = dest.chars().peekable();
-    
-    // Skip "S " or "U " prefix
-    let first_char = chars.peek().copied();
-    if first_char == Some('S') || first_char == Some('U') {
-        chars.next();
-        if chars.peek() == Some(&' ') {
-            chars.next();
-        } else {
-            // Put back the S/U if it's not followed by space
-            if let Some(c) = first_char {
-                result.push(c);
+
+// "S+U Warschauer Str." and "S+U Warschauer Str. (Berlin)" are the same stop
+// Compared with the built-in cleanup only, custom aliases don't get in the way
+fn is_same_stop(direction: &str, stop: &str) -> bool {
+    destination::clean(direction, &[], true) == destination::clean(stop, &[], true)
+}
+
+// Fetch all configured stops and merge their departures (or arrivals), closest first
+// Departures that can't be reached on foot any more and hidden lines are dropped.
+// A failing stop is skipped as long as another one answers.
+pub fn fetch_stops(
+    agent: &ureq::Agent,
+    stops: &[StopConfig],
+    fetch: &FetchConfig,
+) -> Result<Vec<Departure>, Box<dyn Error>> {
+    fetch_stops_with(agent, stops, fetch, || {})
+}
+
+// Same as fetch_stops, `before_stop` runs before every request
+// Each stop can take up to the agent timeout, the main loops ping the systemd watchdog here
+pub fn fetch_stops_with(
+    agent: &ureq::Agent,
+    stops: &[StopConfig],
+    fetch: &FetchConfig,
+    mut before_stop: impl FnMut(),
+) -> Result<Vec<Departure>, Box<dyn Error>> {
+    let mut departures = Vec::new();
+    let mut last_error = None;
+
+    for stop in stops {
+        before_stop();
+        match fetch_board(agent, &stop.id, fetch.mode, fetch) {
+            Ok(stop_departures) => departures.extend(with_urgency(stop_departures, stop)),
+            Err(e) => {
+                warn!(stop_id = %stop.id, "Stop failed: {}", e);
+                last_error = Some(e);
             }
         }
     }
-    
-    // Process rest of string, skipping " (Berlin)" and " Bhf"
-    let mut buffer = String::with_capacity(dest.len());
-    while let Some(ch) = chars.next() {
-        buffer.push(ch);
-        
-        // Check for " (Berlin)" suffix
-        if buffer.ends_with(" (Berlin)") {
-            buffer.truncate(buffer.len() - 9);
-            break;
-        }
-        
-        // Check for " Bhf" suffix
-        if buffer.ends_with(" Bhf") {
-            buffer.truncate(buffer.len() - 4);
-            break;
-        }
-        
-        // Check for Ringbahn symbols
-        if buffer.ends_with(" ⟲") || buffer.ends_with(" ⟳") {
-            buffer.truncate(buffer.len() - 2);
-            break;
-        }
+
+    if let Some(e) = last_error
+        && departures.is_empty()
+    {
+        return Err(e);
     }
-    
-    result.push_str(&buffer);
-    drop(buffer); // Explicitly free buffer
-    result
+
+    departures.retain(|d| !fetch.hide_lines.contains(&d.line));
+    departures.sort_by_key(|d| d.minutes);
+    Ok(departures)
+}
+
+// Classify by walking time and hide departures that are already out of reach
+pub fn with_urgency(departures: Vec<Departure>, stop: &StopConfig) -> Vec<Departure> {
+    let Some(walking_time) = stop.walking_time else {
+        return departures;
+    };
+
+    departures
+        .into_iter()
+        .map(|d| {
+            let urgency = Urgency::classify(d.minutes, walking_time, stop.leave_now);
+            d.with_urgency(Some(urgency))
+        })
+        .filter(|d| d.urgency != Some(Urgency::TooLate))
+        .collect()
 }
 
 // Hardcoded for Warschauer Str for now
 // TODO: make station ID configurable via config file or CLI args
 pub fn fetch_warschauer_str(agent: &ureq::Agent) -> Result<Vec<Departure>, Box<dyn Error>> {
-    fetch_departures(agent, "900120003")
+    fetch_departures(agent, "900120003", &FetchConfig::default())
 }
 
 #[cfg(test)]
@@ -245,5 +438,71 @@ mod tests {
         let parsed = DateTime::parse_from_rfc3339(&future_str);
         assert!(parsed.is_ok());
     }
+
+    const ARRIVALS: &str = r#"{"arrivals": [
+        {"line": {"name": "S3"}, "provenance": "S Erkner Bhf", "when": "2025-03-10T09:05:00+01:00",
+         "plannedWhen": "2025-03-10T09:04:00+01:00", "delay": 60, "platform": "2"},
+        {"line": {"name": "S41"}, "provenance": "Ringbahn S41 ⟳", "when": "2025-03-10T09:03:00+01:00",
+         "stop": {"name": "S Ostkreuz Bhf (Berlin)"}},
+        {"line": {"name": "RE1"}, "provenance": "Frankfurt (Oder)", "when": "2025-03-10T09:04:00+01:00"},
+        {"line": {"name": "U1"}, "provenance": "S+U Warschauer Str. (Berlin)", "when": "2025-03-10T09:06:00+01:00"},
+        {"line": {"name": "M10"}, "provenance": "S+U Hauptbahnhof", "when": null,
+         "plannedWhen": "2025-03-10T09:07:00+01:00", "cancelled": true},
+        {"line": {"name": "S5"}, "provenance": "S Strausberg Nord", "when": "2025-03-10T09:30:00+01:00"}
+    ]}"#;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-03-10T09:00:00+01:00").unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_arrivals() {
+        let fetch = FetchConfig { ringbahn: true, ..FetchConfig::default() };
+        let arrivals = parse_board(ARRIVALS, "900120005", BoardMode::Arrivals, &fetch, now()).unwrap();
+
+        let board: Vec<(&str, &str, u32)> =
+            arrivals.iter().map(|a| (a.line.as_str(), a.destination.as_str(), a.minutes)).collect();
+        // Origins cleaned, regional trains and the far future dropped
+        // The S41 (clockwise) came through Gesundbrunnen
+        assert_eq!(
+            board,
+            [("S41", "Gesundbrunnen", 3), ("S3", "Erkner", 5), ("U1", "Warschauer Str.", 6), ("M10", "Hauptbahnhof", 7)]
+        );
+
+        assert_eq!(arrivals[1].delay, Some(1));
+        assert_eq!(arrivals[1].platform.as_deref(), Some("2"));
+        assert_eq!(arrivals[1].stop.as_deref(), Some("900120005"));
+        assert!(arrivals[3].cancelled);
+        assert_eq!(arrivals[0].ring, Some(RingDirection::Clockwise));
+    }
+
+    const DEPARTURES: &str = r#"{"departures": [
+        {"line": {"name": "U1"}, "direction": "S+U Warschauer Str.", "when": "2025-03-10T09:04:00+01:00",
+         "stop": {"name": "S+U Warschauer Str. (Berlin)"}},
+        {"line": {"name": "U1"}, "direction": "S+U Warschauer Str.", "when": "2025-03-10T09:05:00+01:00",
+         "stop": {"name": "U Kottbusser Tor (Berlin)"}},
+        {"line": {"name": "U1"}, "direction": "U Uhlandstr. (Berlin)", "when": "2025-03-10T09:06:00+01:00",
+         "stop": {"name": "S+U Warschauer Str. (Berlin)"}}
+    ]}"#;
+
+    #[test]
+    fn test_skip_departures_to_own_stop() {
+        // Both stops are fetched into one response here, the stop name decides
+        let departures = parse_board(DEPARTURES, "900120003", BoardMode::Departures, &FetchConfig::default(), now()).unwrap();
+        let board: Vec<(&str, u32)> = departures.iter().map(|d| (d.destination.as_str(), d.minutes)).collect();
+        assert_eq!(board, [("Warschauer Str.", 5), ("Uhlandstr.", 6)]);
+    }
+
+    #[test]
+    fn test_parse_arrivals_without_ringbahn() {
+        let arrivals = parse_board(ARRIVALS, "900120005", BoardMode::Arrivals, &FetchConfig::default(), now()).unwrap();
+        assert!(arrivals.iter().all(|a| a.line != "S41"));
+
+        // The departures list of the same response is empty
+        let departures = parse_board(ARRIVALS, "900120005", BoardMode::Departures, &FetchConfig::default(), now());
+        assert!(departures.unwrap().is_empty());
+        let error = parse_board("not json", "900120005", BoardMode::Arrivals, &FetchConfig::default(), now()).unwrap_err();
+        assert_eq!(FetchError::kind_of(error.as_ref()), ErrorKind::Parse);
+    }
 }
 
@@ -0,0 +1,129 @@
+use crate::api;
+use crate::config::{BoardMode, FetchConfig, StopConfig};
+use crate::departure::Departure;
+use crate::destination::Rule;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Oldest board still shown while the API is down, minutes are counted down
+pub const STALE_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Last fetched board on disk, shared by `rusty-bvg line` invocations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cache {
+    pub fetched: DateTime<Utc>,
+    /// Settings the board was fetched with, another config needs a new fetch
+    pub key: CacheKey,
+    pub departures: Vec<Departure>,
+}
+
+/// Everything in the config that changes the cached board
+/// `fetch.interval` and the stop names don't, they are left out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub stops: Vec<StopKey>,
+    pub mode: BoardMode,
+    pub hide_lines: Vec<String>,
+    pub destinations: Vec<Rule>,
+    pub default_destinations: bool,
+    pub ringbahn: bool,
+}
+
+/// The parts of a stop that decide which departures are kept and their urgency
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StopKey {
+    pub id: String,
+    pub walking_time: Option<u32>,
+    pub leave_now: u32,
+}
+
+impl CacheKey {
+    pub fn new(stops: &[StopConfig], fetch: &FetchConfig) -> Self {
+        Self {
+            stops: stops
+                .iter()
+                .map(|s| StopKey { id: s.id.clone(), walking_time: s.walking_time, leave_now: s.leave_now })
+                .collect(),
+            mode: fetch.mode,
+            hide_lines: fetch.hide_lines.clone(),
+            destinations: fetch.destinations.clone(),
+            default_destinations: fetch.default_destinations,
+            ringbahn: fetch.ringbahn,
+        }
+    }
+}
+
+impl Cache {
+    pub fn new(stops: &[StopConfig], fetch: &FetchConfig, departures: Vec<Departure>, fetched: DateTime<Utc>) -> Self {
+        Self {
+            fetched,
+            key: CacheKey::new(stops, fetch),
+            departures,
+        }
+    }
+
+    /// None if the file is missing or unreadable, the caller fetches then
+    /// The temp dir is shared: files of other users, symlinks or files others can write are ignored
+    pub fn load(path: &Path) -> Option<Self> {
+        let mut file = OpenOptions::new().read(true).custom_flags(libc::O_NOFOLLOW).open(path).ok()?;
+        let metadata = file.metadata().ok()?;
+        // geteuid always succeeds
+        if metadata.uid() != unsafe { libc::geteuid() } || metadata.mode() & 0o777 != 0o600 {
+            return None;
+        }
+        let mut content = String::new();
+        file.read_to_string(&mut content).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Write to a new temporary file first, a parallel reader never sees half a file
+    /// O_EXCL: never write through a file or symlink someone else put there
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string(self).map_err(io::Error::other)?;
+        // A leftover from a crashed run under a reused pid must not block every save
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+        let tmp = path.with_extension(format!("tmp.{}.{}", std::process::id(), nanos));
+        let mut file = OpenOptions::new().write(true).create_new(true).mode(0o600).open(&tmp)?;
+        if let Err(e) = file.write_all(json.as_bytes()) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e);
+        }
+        fs::rename(&tmp, path)
+    }
+
+    /// Young enough and fetched for these stops with these settings
+    pub fn is_fresh(&self, stops: &[StopConfig], fetch: &FetchConfig, now: DateTime<Utc>, max_age: Duration) -> bool {
+        let age = now.signed_duration_since(self.fetched);
+        self.key == CacheKey::new(stops, fetch) && age >= chrono::Duration::zero() && age.to_std().is_ok_and(|age| age <= max_age)
+    }
+
+    /// The cached departures counted down to `now`, gone ones dropped
+    /// Urgency is classified again, departures out of reach by now are dropped too
+    pub fn departures_at(&self, now: DateTime<Utc>, stops: &[StopConfig]) -> Vec<Departure> {
+        let elapsed = now.signed_duration_since(self.fetched).num_minutes().max(0);
+        let elapsed = u32::try_from(elapsed).unwrap_or(u32::MAX);
+        self.departures
+            .iter()
+            .filter(|d| d.minutes >= elapsed)
+            .map(|d| Departure { minutes: d.minutes - elapsed, ..d.clone() })
+            .flat_map(|d| match stops.iter().find(|s| d.stop.as_deref() == Some(s.id.as_str())) {
+                Some(stop) => api::with_urgency(vec![d], stop),
+                None => vec![d],
+            })
+            .collect()
+    }
+}
+
+/// $XDG_RUNTIME_DIR/rusty-bvg-line.json, or a per-user file in the temp dir
+pub fn default_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("rusty-bvg-line.json"),
+        // getuid always succeeds
+        None => std::env::temp_dir().join(format!("rusty-bvg-line-{}.json", unsafe { libc::getuid() })),
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+
+/// Plain RGB colour, independent of the LED matrix bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Rgb {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Parse "#RRGGBB" or the short "#RGB" form (leading '#' optional)
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if !hex.is_ascii() {
+            return None;
+        }
+
+        match hex.len() {
+            6 => Some(Self::new(
+                u8::from_str_radix(&hex[0..2], 16).ok()?,
+                u8::from_str_radix(&hex[2..4], 16).ok()?,
+                u8::from_str_radix(&hex[4..6], 16).ok()?,
+            )),
+            3 => {
+                // "#f0a" -> "#ff00aa"
+                let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|d| d * 17);
+                Some(Self::new(digit(0)?, digit(1)?, digit(2)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// "#RRGGBB"
+    pub fn to_hex(self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Rgb::from_hex(&hex)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid colour '{}', expected #RRGGBB", hex)))
+    }
+}
+
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+// BVG yellow/amber, used for all non-badge text
+pub const AMBER: Rgb = Rgb::new(255, 200, 0);
+pub const WHITE: Rgb = Rgb::new(255, 255, 255);
+pub const BLACK: Rgb = Rgb::new(0, 0, 0);
+
+/// Badge colours for a single line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineColor {
+    /// Badge background
+    pub bg: Rgb,
+    /// Line name drawn on top of the badge
+    pub fg: Rgb,
+}
+
+impl LineColor {
+    pub const fn new(bg: Rgb, fg: Rgb) -> Self {
+        Self { bg, fg }
+    }
+}
+
+/// Badge shape, following the BVG signage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeShape {
+    /// U-Bahn, trams and everything else: square box
+    Rect,
+    /// S-Bahn: rounded pill
+    Pill,
+}
+
+impl BadgeShape {
+    pub fn for_line(line: &str) -> Self {
+        if is_sbahn(line) {
+            BadgeShape::Pill
+        } else {
+            BadgeShape::Rect
+        }
+    }
+}
+
+fn is_sbahn(line: &str) -> bool {
+    line.starts_with('S') && line[1..].chars().next().is_some_and(|c| c.is_ascii_digit())
+}
+
+const fn hex(value: u32) -> Rgb {
+    Rgb::new((value >> 16) as u8, (value >> 8) as u8, value as u8)
+}
+
+// Fallback when neither the API nor the table knows the line
+const DEFAULT_BADGE: LineColor = LineColor::new(hex(0x333333), AMBER);
+
+// MetroTrams share the BVG tram red
+const TRAM: LineColor = LineColor::new(hex(0xBE1414), WHITE);
+
+// Generic S-Bahn green for lines missing from the table
+const SBAHN: LineColor = LineColor::new(hex(0x008D4F), WHITE);
+
+/// Official VBB line colours
+/// Used when the API response doesn't carry `line.color`
+pub fn vbb_line_color(line: &str) -> Option<LineColor> {
+    let color = match line {
+        "U1" => LineColor::new(hex(0x7DAD4C), WHITE),
+        "U2" => LineColor::new(hex(0xDA421E), WHITE),
+        "U3" => LineColor::new(hex(0x16683D), WHITE),
+        "U4" => LineColor::new(hex(0xF0D722), BLACK),
+        "U5" | "U55" => LineColor::new(hex(0x7E5330), WHITE),
+        "U6" => LineColor::new(hex(0x8C6DAB), WHITE),
+        "U7" => LineColor::new(hex(0x528DBA), WHITE),
+        "U8" => LineColor::new(hex(0x224F86), WHITE),
+        "U9" => LineColor::new(hex(0xF3791D), WHITE),
+        "S1" => LineColor::new(hex(0xDE4DA4), WHITE),
+        "S2" | "S25" | "S26" => LineColor::new(hex(0x005F27), WHITE),
+        "S3" => LineColor::new(hex(0x0A4C99), WHITE),
+        "S41" => LineColor::new(hex(0xA23B1E), WHITE),
+        "S42" => LineColor::new(hex(0xC26A36), WHITE),
+        "S45" | "S46" | "S47" => LineColor::new(hex(0xC38737), WHITE),
+        "S5" => LineColor::new(hex(0xFF5900), WHITE),
+        "S7" | "S75" => LineColor::new(hex(0x6F4E9C), WHITE),
+        "S8" | "S85" => LineColor::new(hex(0x55A822), WHITE),
+        "S9" => LineColor::new(hex(0x8A0E30), WHITE),
+        _ if is_sbahn(line) => SBAHN,
+        // MetroTrams (M1, M10, ...), Metro buses fall through to None
+        _ if is_tram(line) => TRAM,
+        _ => return None,
+    };
+    Some(color)
+}
+
+fn is_tram(line: &str) -> bool {
+    // Metro buses (M29, M41, ...) share the prefix, only these numbers are trams
+    // Numbered trams (12, 16, ...) never get here, parse_board drops numeric lines as buses
+    const METRO_TRAMS: [&str; 9] = ["M1", "M2", "M4", "M5", "M6", "M8", "M10", "M13", "M17"];
+    METRO_TRAMS.contains(&line)
+}
+
+/// Pick the badge colours for a line
+/// Priority: config override, then API colour, then built-in VBB table
+pub fn resolve_line_color(
+    line: &str,
+    api_color: Option<LineColor>,
+    overrides: &HashMap<String, LineColor>,
+) -> LineColor {
+    overrides
+        .get(line)
+        .copied()
+        .or(api_color)
+        .or_else(|| vbb_line_color(line))
+        .unwrap_or(DEFAULT_BADGE)
+}
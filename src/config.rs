@@ -0,0 +1,354 @@
+use crate::colors::LineColor;
+use crate::control::HttpConfig;
+use crate::destination::Rule;
+use crate::mqtt::MqttConfig;
+use crate::font::FontChoice;
+use crate::line::LineConfig;
+use crate::locale::Locale;
+use crate::pages::Page;
+use crate::privileges::PrivilegesConfig;
+use crate::schedule::Schedule;
+use crate::status::StatusBarConfig;
+use crate::template::FormatConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+// S+U Warschauer Str., the board's original home
+const DEFAULT_STOP_ID: &str = "900120003";
+
+/// Top-level config file (TOML)
+/// Every section is optional, missing values fall back to the defaults
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Language of the board, console and terminal texts, "en" or "de"
+    pub locale: Locale,
+    /// Stops to show, `[[stops]]` in the config. Departures of all stops are merged.
+    pub stops: Vec<StopConfig>,
+    pub fetch: FetchConfig,
+    pub display: DisplayConfig,
+    pub privileges: PrivilegesConfig,
+    /// Status and control API, off unless configured (needs the `http` feature)
+    pub http: Option<HttpConfig>,
+    /// MQTT output, off unless configured (needs the `mqtt` feature)
+    pub mqtt: Option<MqttConfig>,
+    /// `rusty-bvg line` output for status bars
+    pub line: LineConfig,
+    /// Departure text templates
+    pub format: FormatConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            locale: Locale::default(),
+            stops: vec![StopConfig::new(DEFAULT_STOP_ID)],
+            fetch: FetchConfig::default(),
+            display: DisplayConfig::default(),
+            privileges: PrivilegesConfig::default(),
+            http: None,
+            mqtt: None,
+            line: LineConfig::default(),
+            format: FormatConfig::default(),
+        }
+    }
+}
+
+/// One stop to fetch departures for
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StopConfig {
+    /// VBB stop ID, e.g. "900120003"
+    pub id: String,
+    /// Display name, e.g. for Home Assistant
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Minutes from the door to the platform, enables leave-now highlighting
+    #[serde(default)]
+    pub walking_time: Option<u32>,
+    /// Departures up to this many minutes after the walking time are "leave now"
+    #[serde(default = "default_leave_now")]
+    pub leave_now: u32,
+}
+
+fn default_leave_now() -> u32 {
+    2
+}
+
+/// What the board lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardMode {
+    #[default]
+    Departures,
+    /// Trains coming in, with their origin instead of the destination
+    Arrivals,
+}
+
+impl BoardMode {
+    /// Endpoint below /stops/{id}/
+    pub fn path(self) -> &'static str {
+        match self {
+            BoardMode::Departures => "departures",
+            BoardMode::Arrivals => "arrivals",
+        }
+    }
+}
+
+/// How often and what to fetch, `[fetch]` in the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FetchConfig {
+    /// Seconds between API requests
+    pub interval: u64,
+    /// Departures or arrivals
+    pub mode: BoardMode,
+    /// Lines never shown, e.g. `["U1", "M10"]`
+    pub hide_lines: Vec<String>,
+    /// Destination cleanup, run before the built-in rules
+    pub destinations: Vec<Rule>,
+    /// Built-in VBB cleanup ("S ", " Bhf", " (Berlin)", ...) after `destinations`
+    pub default_destinations: bool,
+    /// Keep the Ringbahn (S41/S42), shown with a direction arrow and the next interchange
+    pub ringbahn: bool,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            interval: 20,
+            mode: BoardMode::default(),
+            hide_lines: Vec::new(),
+            destinations: Vec::new(),
+            default_destinations: true,
+            ringbahn: false,
+        }
+    }
+}
+
+impl StopConfig {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: None,
+            walking_time: None,
+            leave_now: default_leave_now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// Width of a single panel in pixels
+    pub width: u32,
+    /// Height of a single panel in pixels
+    pub height: u32,
+    /// Number of daisy-chained panels
+    pub chain_length: u32,
+    /// Number of parallel chains (1-3, depends on the adapter board)
+    pub parallel: u32,
+    /// Hardware mapping (e.g., "regular", "adafruit-hat", etc.)
+    pub hardware_mapping: String,
+    /// Pixel mapper chain, e.g. "U-mapper;Rotate:90"
+    pub pixel_mapper: Option<String>,
+    /// PWM bits (1-11), lower means faster refresh but fewer colours
+    pub pwm_bits: u8,
+    /// Brightness in percent (1-100)
+    pub brightness: u8,
+    /// 0 = progressive, 1 = interlaced
+    pub scan_mode: u32,
+    /// GPIO slowdown, faster Pis need higher values (0-4)
+    pub gpio_slowdown: Option<u32>,
+    /// "4x6", "5x7", "6x10" or a path to a BDF font
+    pub font: FontChoice,
+    /// Brightness schedule and night mode, see schedule.rs
+    pub schedule: Schedule,
+    /// Clock and data-age bar, off unless configured
+    pub status_bar: Option<StatusBarConfig>,
+    /// Page rotation, defaults to cycling through single departures
+    pub pages: Vec<Page>,
+    /// Length of page transitions in milliseconds
+    pub transition_ms: u64,
+    /// Frame rate of page transitions
+    pub transition_fps: u32,
+    /// Blink the minutes of departures you have to leave for now
+    pub blink_leave_now: bool,
+    /// Per-line badge colours, e.g. `U1 = { bg = "#7DAD4C", fg = "#FFFFFF" }`
+    /// Takes precedence over the API colours and the built-in VBB table
+    pub line_colors: HashMap<String, LineColor>,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            width: 64,
+            height: 32,
+            chain_length: 1,
+            parallel: 1,
+            hardware_mapping: "regular".to_string(),
+            pixel_mapper: None,
+            pwm_bits: 11,
+            brightness: 100,
+            scan_mode: 0,
+            gpio_slowdown: None,
+            font: FontChoice::default(),
+            schedule: Schedule::default(),
+            status_bar: None,
+            pages: Vec::new(),
+            transition_ms: 400,
+            transition_fps: 30,
+            blink_leave_now: false,
+            line_colors: HashMap::new(),
+        }
+    }
+}
+
+impl DisplayConfig {
+    /// Size of the drawing canvas after chaining and pixel mapping
+    /// Follows the mappers built into rpi-rgb-led-matrix
+    pub fn canvas_size(&self) -> (u32, u32) {
+        let mut width = self.width * self.chain_length.max(1);
+        let mut height = self.height * self.parallel.max(1);
+
+        for mapper in self.pixel_mapper.iter().flat_map(|m| m.split(';')) {
+            let (name, param) = match mapper.split_once(':') {
+                Some((name, param)) => (name.trim(), param.trim()),
+                None => (mapper.trim(), ""),
+            };
+
+            match name {
+                // Folds a long chain into two rows
+                "U-mapper" => (width, height) = (width / 2, height * 2),
+                "Rotate" if matches!(param, "90" | "270") => (width, height) = (height, width),
+                _ => {}
+            }
+        }
+
+        (width, height)
+    }
+
+    /// Take over the panel settings of the running display
+    /// They are only applied when the matrix is initialised, changing them needs a restart.
+    /// Returns true if any of them differed.
+    pub fn adopt_panel(&mut self, running: &DisplayConfig) -> bool {
+        let panel = |c: &DisplayConfig| {
+            (
+                c.width,
+                c.height,
+                c.chain_length,
+                c.parallel,
+                c.hardware_mapping.clone(),
+                c.pixel_mapper.clone(),
+                c.pwm_bits,
+                c.scan_mode,
+                c.gpio_slowdown,
+            )
+        };
+        if panel(self) == panel(running) {
+            return false;
+        }
+
+        self.width = running.width;
+        self.height = running.height;
+        self.chain_length = running.chain_length;
+        self.parallel = running.parallel;
+        self.hardware_mapping = running.hardware_mapping.clone();
+        self.pixel_mapper = running.pixel_mapper.clone();
+        self.pwm_bits = running.pwm_bits;
+        self.scan_mode = running.scan_mode;
+        self.gpio_slowdown = running.gpio_slowdown;
+        true
+    }
+
+    /// Reject values the matrix library would refuse anyway, with a readable message
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err("display.width and display.height must be > 0".to_string());
+        }
+        if self.chain_length == 0 || !(1..=3).contains(&self.parallel) {
+            return Err("display.chain_length must be >= 1, display.parallel 1-3".to_string());
+        }
+        if !(1..=11).contains(&self.pwm_bits) {
+            return Err(format!("display.pwm_bits must be 1-11, got {}", self.pwm_bits));
+        }
+        if !(1..=100).contains(&self.brightness) {
+            return Err(format!("display.brightness must be 1-100, got {}", self.brightness));
+        }
+        if self.scan_mode > 1 {
+            return Err(format!("display.scan_mode must be 0 or 1, got {}", self.scan_mode));
+        }
+        if !(1..=60).contains(&self.transition_fps) {
+            return Err(format!("display.transition_fps must be 1-60, got {}", self.transition_fps));
+        }
+        self.pages.iter().try_for_each(Page::validate)?;
+        self.schedule.validate()
+    }
+}
+
+impl Config {
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn Error>> {
+        let config: Config = toml::from_str(text).map_err(|e| format!("Config parse error: {}", e))?;
+        if config.stops.is_empty() || config.stops.iter().any(|stop| stop.id.is_empty()) {
+            return Err("stops: at least one stop with an id is needed".into());
+        }
+        if config.fetch.interval < 5 {
+            return Err(format!("fetch.interval must be >= 5 seconds, got {}", config.fetch.interval).into());
+        }
+        if config.line.count == 0 {
+            return Err("line.count must be > 0".into());
+        }
+        config.display.validate()?;
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config {}: {}", path.display(), e))?;
+        let config = Self::from_toml(&text)?;
+        info!(path = %path.display(), "Config loaded");
+        Ok(config)
+    }
+}
+
+/// Notices when the config file was written, by polling its modification time
+pub struct ConfigWatcher {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let modified = path.as_deref().and_then(modified_time);
+        Self { path, modified }
+    }
+
+    /// True once after each change of the file
+    pub fn changed(&mut self) -> bool {
+        let Some(path) = &self.path else {
+            return false;
+        };
+
+        let modified = modified_time(path);
+        if modified == self.modified {
+            return false;
+        }
+        // A missing file (editor replacing it) counts as no change until it's back
+        // Forgetting the old time warns only once and reloads whatever file reappears
+        if modified.is_none() {
+            warn!(path = %path.display(), "Config file disappeared");
+            self.modified = None;
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,398 @@
+use crate::api::FetchError;
+use crate::config::FetchConfig;
+use crate::departure::Departure;
+use crate::metrics::Metrics;
+use crate::output::Entry;
+use crate::pages::{PageKind, Priority};
+use crate::status::Freshness;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Longest message accepted for the board, it has to fit a few rows anyway
+const MAX_MESSAGE_CHARS: usize = 200;
+const MAX_MESSAGE_SECS: u64 = 3600;
+// Announcements stay in the rotation for a day at most
+const MAX_TTL_SECS: u64 = 86400;
+// Request bodies are tiny JSON objects
+const MAX_BODY_BYTES: u64 = 4096;
+
+/// Status and control API, `[http]` in the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Address to listen on, "0.0.0.0:8080" to open it to the network
+    #[serde(default = "default_listen")]
+    pub listen: String,
+    /// Needed as `Authorization: Bearer <token>` to change the board, reading stays open
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+fn default_listen() -> String {
+    "127.0.0.1:8080".to_string()
+}
+
+impl HttpConfig {
+    /// Anyone who can reach the address may change the board
+    pub fn is_unprotected(&self) -> bool {
+        let public = self
+            .listen
+            .parse::<SocketAddr>()
+            .map_or(true, |address| !address.ip().is_loopback());
+        public && self.token.is_none()
+    }
+}
+
+/// One HTTP request, independent of the server
+#[derive(Debug, Clone, Copy)]
+pub struct Request<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub body: &'a str,
+    /// Value of the Authorization header
+    pub authorization: Option<&'a str>,
+}
+
+impl<'a> Request<'a> {
+    pub fn new(method: &'a str, path: &'a str, body: &'a str) -> Self {
+        Self { method, path, body, authorization: None }
+    }
+
+    pub fn with_authorization(mut self, authorization: &'a str) -> Self {
+        self.authorization = Some(authorization);
+        self
+    }
+}
+
+/// Where the control routes send commands, and who may use them
+#[derive(Debug, Clone, Default)]
+pub struct Control {
+    /// None without a display, the control routes are then not there
+    commands: Option<Sender<Command>>,
+    token: Option<String>,
+}
+
+impl Control {
+    pub fn new(commands: Option<Sender<Command>>, token: Option<String>) -> Self {
+        Self { commands, token }
+    }
+
+    fn is_authorized(&self, authorization: Option<&str>) -> bool {
+        let Some(token) = &self.token else {
+            return true;
+        };
+        let given = authorization.and_then(|a| a.strip_prefix("Bearer ")).unwrap_or_default();
+        // Same time for every wrong token of the right length
+        given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+    }
+}
+
+/// Requests from outside for the main loop, over HTTP or MQTT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Text page shown for `duration` seconds at a time,
+    /// part of the rotation for `ttl` seconds
+    Message {
+        text: String,
+        duration: u64,
+        ttl: u64,
+        priority: Priority,
+    },
+    /// Remove all messages from the rotation
+    ClearMessages,
+    /// Fixed brightness in percent (0 = off), None goes back to the schedule
+    Brightness(Option<u8>),
+    /// Show a page right away for `duration` seconds
+    ShowPage { kind: PageKind, duration: u64 },
+    /// Switch the panel off until unblanked, wins over brightness and schedule
+    Blank(bool),
+}
+
+/// What the main loop shares with the API
+#[derive(Debug)]
+pub struct BoardState {
+    /// Departures of the last successful fetch
+    pub departures: Vec<Departure>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub fetches: u64,
+    pub fetch_errors: u64,
+    pub last_error: Option<String>,
+    /// Exported on /metrics
+    pub metrics: Metrics,
+    /// `fetch.interval`, /health calls data stale after a few missed fetches
+    pub fetch_interval: u64,
+}
+
+impl Default for BoardState {
+    fn default() -> Self {
+        Self {
+            departures: Vec::new(),
+            last_success: None,
+            fetches: 0,
+            fetch_errors: 0,
+            last_error: None,
+            metrics: Metrics::default(),
+            fetch_interval: FetchConfig::default().interval,
+        }
+    }
+}
+
+pub type SharedState = Arc<Mutex<BoardState>>;
+
+impl BoardState {
+    pub fn record_fetch(
+        &mut self,
+        result: &Result<Vec<Departure>, Box<dyn Error>>,
+        now: DateTime<Utc>,
+        elapsed: Duration,
+    ) {
+        self.fetches += 1;
+        self.metrics.fetch_duration.observe(elapsed.as_secs_f64());
+        match result {
+            Ok(departures) => {
+                self.departures.clone_from(departures);
+                self.last_success = Some(now);
+                self.metrics.fetch_success += 1;
+                self.metrics.departures = departures.len();
+            }
+            Err(e) => {
+                let message = e.to_string();
+                self.fetch_errors += 1;
+                *self.metrics.fetch_errors.entry(FetchError::kind_of(e.as_ref())).or_default() += 1;
+                self.last_error = Some(message);
+            }
+        }
+    }
+
+    /// The latest successful fetch found any departures
+    /// The display keeps showing older ones after an empty fetch, this doesn't
+    pub fn has_departures(&self) -> bool {
+        !self.departures.is_empty()
+    }
+
+    pub fn record_render(&mut self, elapsed: Duration) {
+        self.metrics.render_duration.observe(elapsed.as_secs_f64());
+    }
+}
+
+/// Answer to a request, JSON except for /metrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        let body = serde_json::to_string(value).unwrap_or_else(|_| "{}".to_string());
+        Self { status, content_type: "application/json", body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+
+    fn accepted() -> Self {
+        Self::json(202, &serde_json::json!({ "ok": true }))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Health<'a> {
+    status: &'static str,
+    last_success: Option<String>,
+    age_seconds: Option<i64>,
+    fetches: u64,
+    fetch_errors: u64,
+    last_error: Option<&'a str>,
+}
+
+/// JSON form of a command, `{"command": "message", "text": "..."}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case", deny_unknown_fields)]
+enum CommandRequest {
+    Message {
+        text: String,
+        #[serde(default = "default_message_duration")]
+        duration: u64,
+        /// Defaults to `duration`: shown once
+        #[serde(default)]
+        ttl: Option<u64>,
+        #[serde(default)]
+        priority: Priority,
+    },
+    ClearMessages,
+    Brightness {
+        /// null = back to the schedule
+        brightness: Option<u8>,
+    },
+    Page {
+        page: PageKind,
+        #[serde(default = "default_message_duration")]
+        duration: u64,
+    },
+    Blank {
+        #[serde(default = "default_blank")]
+        blank: bool,
+    },
+}
+
+fn default_message_duration() -> u64 {
+    30
+}
+
+fn default_blank() -> bool {
+    true
+}
+
+/// Parse and check a command, the same JSON over HTTP and MQTT
+pub fn parse_command(json: &str) -> Result<Command, String> {
+    let request: CommandRequest = serde_json::from_str(json).map_err(|e| format!("invalid command: {}", e))?;
+    let check_duration = |duration: u64| {
+        if (1..=MAX_MESSAGE_SECS).contains(&duration) {
+            Ok(duration)
+        } else {
+            Err("duration must be 1-3600 seconds".to_string())
+        }
+    };
+
+    match request {
+        CommandRequest::Message { text, duration, ttl, priority } => {
+            let text = text.trim();
+            if text.is_empty() || text.chars().count() > MAX_MESSAGE_CHARS {
+                return Err("text must be 1-200 characters".to_string());
+            }
+            let duration = check_duration(duration)?;
+            let ttl = ttl.unwrap_or(duration);
+            if !(1..=MAX_TTL_SECS).contains(&ttl) {
+                return Err("ttl must be 1-86400 seconds".to_string());
+            }
+            Ok(Command::Message { text: text.to_string(), duration, ttl, priority })
+        }
+        CommandRequest::ClearMessages => Ok(Command::ClearMessages),
+        CommandRequest::Brightness { brightness } => {
+            if brightness.is_some_and(|b| b > 100) {
+                return Err("brightness must be 0-100 or null".to_string());
+            }
+            Ok(Command::Brightness(brightness))
+        }
+        CommandRequest::Page { page, duration } => {
+            // Text pages need a text, that's a message
+            if page == PageKind::Text {
+                return Err("page can't be text, send a message instead".to_string());
+            }
+            Ok(Command::ShowPage { kind: page, duration: check_duration(duration)? })
+        }
+        CommandRequest::Blank { blank } => Ok(Command::Blank(blank)),
+    }
+}
+
+/// Body of a single-purpose endpoint, e.g. POST /message, as command
+fn parse_endpoint(command: &str, body: &str) -> Result<Command, String> {
+    let mut value: serde_json::Value = serde_json::from_str(body).map_err(|e| format!("invalid command: {}", e))?;
+    let Some(fields) = value.as_object_mut() else {
+        return Err("invalid command: expected a JSON object".to_string());
+    };
+    fields.insert("command".to_string(), command.into());
+    parse_command(&value.to_string())
+}
+
+/// Read a request body, on error the response to send instead of routing it
+/// 413 when it is longer than 4 KiB, 400 when it can't be read or isn't UTF-8
+pub fn read_body(reader: impl Read) -> Result<String, Response> {
+    let mut bytes = Vec::new();
+    reader
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| Response::error(400, &format!("failed to read body: {}", e)))?;
+    if bytes.len() as u64 > MAX_BODY_BYTES {
+        return Err(Response::error(413, "body too large"));
+    }
+    String::from_utf8(bytes).map_err(|_| Response::error(400, "body is not UTF-8"))
+}
+
+/// Route one request, independent of the HTTP server
+/// GET /departures, GET /health, GET /metrics,
+/// POST /command (any command), POST|DELETE /message, POST /brightness, POST /page, POST /blank
+pub fn handle(request: &Request, state: &SharedState, control: &Control, now: DateTime<Utc>) -> Response {
+    // Query strings are not used by any endpoint
+    let path = request.path.split('?').next().unwrap_or_default();
+    let body = request.body;
+
+    if !matches!(path, "/command" | "/message" | "/brightness" | "/page" | "/blank") {
+        return read(request.method, path, state, now);
+    }
+    let Some(commands) = &control.commands else {
+        return Response::error(404, "not found, no display to control");
+    };
+    if !control.is_authorized(request.authorization) {
+        return Response::error(401, "missing or wrong bearer token");
+    }
+
+    match (request.method, path) {
+        ("POST", "/command") => send(commands, parse_command(body)),
+        ("POST", "/message") => send(commands, parse_endpoint("message", body)),
+        ("DELETE", "/message") => send(commands, Ok(Command::ClearMessages)),
+        ("POST", "/brightness") => send(commands, parse_endpoint("brightness", body)),
+        ("POST", "/page") => send(commands, parse_endpoint("page", body)),
+        ("POST", "/blank") => send(commands, parse_endpoint("blank", body)),
+        _ => Response::error(405, "method not allowed"),
+    }
+}
+
+/// The read-only routes, open to everyone who can reach the API
+fn read(method: &str, path: &str, state: &SharedState, now: DateTime<Utc>) -> Response {
+    match (method, path) {
+        ("GET", "/departures") => {
+            let state = state.lock().unwrap_or_else(|e| e.into_inner());
+            Response::json(200, &state.departures.iter().map(Entry::from).collect::<Vec<_>>())
+        }
+        ("GET", "/health") => {
+            let state = state.lock().unwrap_or_else(|e| e.into_inner());
+            let status = match Freshness::from_last_success(now, state.last_success, state.fetch_interval) {
+                Freshness::Fresh => "ok",
+                Freshness::Stale => "stale",
+                Freshness::Offline => "offline",
+            };
+            Response::json(
+                200,
+                &Health {
+                    status,
+                    last_success: state.last_success.map(|t| t.to_rfc3339()),
+                    age_seconds: state.last_success.map(|t| now.signed_duration_since(t).num_seconds()),
+                    fetches: state.fetches,
+                    fetch_errors: state.fetch_errors,
+                    last_error: state.last_error.as_deref(),
+                },
+            )
+        }
+        ("GET", "/metrics") => {
+            let state = state.lock().unwrap_or_else(|e| e.into_inner());
+            Response {
+                status: 200,
+                content_type: "text/plain; version=0.0.4",
+                body: state.metrics.render(state.last_success, now),
+            }
+        }
+        (_, "/departures" | "/health" | "/metrics") => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn send(commands: &Sender<Command>, command: Result<Command, String>) -> Response {
+    let command = match command {
+        Ok(command) => command,
+        Err(e) => return Response::error(400, &e),
+    };
+    match commands.send(command) {
+        Ok(()) => Response::accepted(),
+        Err(_) => Response::error(503, "board is shutting down"),
+    }
+}
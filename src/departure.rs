@@ -1,9 +1,39 @@
+use crate::colors::LineColor;
+use crate::locale::Locale;
+use crate::ring::RingDirection;
+use crate::template::{self, CharCount, Template, Words};
+use crate::urgency::Urgency;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+static DEFAULT_TEMPLATE: LazyLock<Template> = LazyLock::new(template::default_template);
+
 // Represents a single departure
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Departure {
     pub line: String,
     pub destination: String,
     pub minutes: u32,
+    /// Line colour as reported by the API (None if the API didn't send one)
+    pub color: Option<LineColor>,
+    /// Disruption warnings attached by the API
+    pub warnings: Vec<String>,
+    /// Leave-now classification, None if the stop has no walking time
+    pub urgency: Option<Urgency>,
+    /// VBB stop ID this departure was fetched for
+    pub stop: Option<String>,
+    /// Minutes late according to the API, negative when early
+    pub delay: Option<i32>,
+    /// Platform or track, mostly for S- and U-Bahn
+    pub platform: Option<String>,
+    /// Timetable departure time, without the delay
+    pub planned: Option<DateTime<Utc>>,
+    /// Cancelled, minutes are then counted to the planned time
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Ringbahn direction, the destination is then the next interchange
+    pub ring: Option<RingDirection>,
 }
 
 impl Departure {
@@ -12,37 +42,105 @@ impl Departure {
             line: line.into(),
             destination: destination.into(),
             minutes,
+            color: None,
+            warnings: Vec::new(),
+            urgency: None,
+            stop: None,
+            delay: None,
+            platform: None,
+            planned: None,
+            cancelled: false,
+            ring: None,
+        }
+    }
+
+    pub fn with_color(mut self, color: Option<LineColor>) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    pub fn with_urgency(mut self, urgency: Option<Urgency>) -> Self {
+        self.urgency = urgency;
+        self
+    }
+
+    pub fn with_stop(mut self, stop: impl Into<String>) -> Self {
+        self.stop = Some(stop.into());
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Option<i32>) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn with_platform(mut self, platform: Option<String>) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    pub fn with_planned(mut self, planned: Option<DateTime<Utc>>) -> Self {
+        self.planned = planned;
+        self
+    }
+
+    pub fn with_cancelled(mut self, cancelled: bool) -> Self {
+        self.cancelled = cancelled;
+        self
+    }
+
+    pub fn with_ring(mut self, ring: Option<RingDirection>) -> Self {
+        self.ring = ring;
+        self
+    }
+
+    // Destination as text, "⟳ Gesundbrunnen" on the Ringbahn
+    pub fn destination_label(&self) -> String {
+        match self.ring {
+            Some(ring) => format!("{} {}", ring.arrow(), self.destination),
+            None => self.destination.clone(),
         }
     }
 
     // Format as "S3 Erkner 2 min"
     pub fn format(&self) -> String {
-        format!("{} {} {} min", self.line, self.destination, self.minutes)
+        self.format_in(Locale::default())
     }
 
-    // Truncate destination to fit within max_chars
-    pub fn format_truncated(&self, max_chars: usize) -> String {
-        let formatted = self.format();
-        if formatted.len() <= max_chars {
-            return formatted;
-        }
+    // Format in the given language, "S3 Erkner 2 Min." in German
+    pub fn format_in(&self, locale: Locale) -> String {
+        DEFAULT_TEMPLATE.render(self, &locale.words())
+    }
 
-        // Calculate space needed for line, minutes, and formatting
-        // Format: "LINE DEST X min"
-        let min_text = format!(" {} min", self.minutes);
-        let line_text = format!("{} ", self.line);
-        let overhead = line_text.len() + min_text.len();
+    // Fill in a template, e.g. "{line} {min}'" gives "S3 2'"
+    pub fn format_with(&self, template: &Template, words: &Words) -> String {
+        template.render(self, words)
+    }
 
-        if overhead >= max_chars {
-            // Can't fit anything, return truncated version
-            return formatted.chars().take(max_chars).collect();
-        }
+    // Truncate destination to fit within max_chars (counted in chars, not bytes)
+    pub fn format_truncated(&self, max_chars: usize) -> String {
+        let max_chars = u32::try_from(max_chars).unwrap_or(u32::MAX);
+        DEFAULT_TEMPLATE.render_fitted(self, &Words::default(), max_chars, &CharCount, |text, width| {
+            text.chars().take(width as usize).collect()
+        })
+    }
+}
 
-        let dest_max_len = max_chars - overhead;
-        let truncated_dest: String = self.destination.chars().take(dest_max_len).collect();
-        
-        format!("{}{}{}", line_text, truncated_dest, min_text)
+/// All distinct warnings of a board, in order of the departures
+/// The same disruption is usually attached to every departure of a line
+pub fn collect_warnings(departures: &[Departure]) -> Vec<&str> {
+    let mut warnings: Vec<&str> = Vec::new();
+    for warning in departures.iter().flat_map(|d| &d.warnings) {
+        if !warnings.contains(&warning.as_str()) {
+            warnings.push(warning);
+        }
     }
+    warnings
 }
 
 /// Generate mock departure data for testing and static display
@@ -0,0 +1,92 @@
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::LazyLock;
+
+/// Built-in VBB cleanup: "S Spandau Bhf (Berlin)" becomes "Spandau"
+static DEFAULT_RULES: LazyLock<Vec<Rule>> = LazyLock::new(|| {
+    let suffix = |s: &str| Rule::StripSuffix { suffix: s.to_string() };
+    let prefix = |s: &str| Rule::StripPrefix { prefix: s.to_string() };
+    vec![
+        suffix(" (Berlin)"),
+        suffix(" ⟲"),
+        suffix(" ⟳"),
+        suffix(" Bhf"),
+        prefix("S+U "),
+        prefix("S "),
+        prefix("U "),
+    ]
+});
+
+/// One step of the destination cleanup, `[[fetch.destinations]]` in the config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Rule {
+    /// "S Erkner" → "Erkner"
+    StripPrefix { prefix: String },
+    /// "Erkner Bhf" → "Erkner"
+    StripSuffix { suffix: String },
+    /// Every match of `pattern` replaced, `$1` inserts a group
+    Replace { pattern: Pattern, with: String },
+    /// The whole name replaced when it is exactly `name`
+    Alias { name: String, to: String },
+}
+
+impl Rule {
+    fn apply(&self, name: &str) -> String {
+        match self {
+            Rule::StripPrefix { prefix } => name.strip_prefix(prefix.as_str()).unwrap_or(name).to_string(),
+            Rule::StripSuffix { suffix } => name.strip_suffix(suffix.as_str()).unwrap_or(name).to_string(),
+            Rule::Replace { pattern, with } => pattern.0.replace_all(name, with.as_str()).into_owned(),
+            Rule::Alias { name: from, to } if from == name => to.clone(),
+            Rule::Alias { .. } => name.to_string(),
+        }
+    }
+}
+
+/// Regex of a replace rule, checked when the config is loaded
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern)
+            .map(Pattern)
+            .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Pattern::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Destination as shown on the board
+/// `rules` run in order, each once, then the built-in ones if `defaults` is set.
+/// A rule that would leave nothing is skipped.
+pub fn clean(name: &str, rules: &[Rule], defaults: bool) -> String {
+    let defaults = if defaults { DEFAULT_RULES.as_slice() } else { &[] };
+    let mut name = name.trim().to_string();
+    for rule in rules.iter().chain(defaults) {
+        let cleaned = rule.apply(&name);
+        if !cleaned.trim().is_empty() {
+            name = cleaned.trim().to_string();
+        }
+    }
+    name
+}
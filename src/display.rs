@@ -1,34 +1,134 @@
 #[cfg(feature = "display")]
-use crate::departure::Departure;
+use crate::colors::{self, BadgeShape, Rgb};
+#[cfg(feature = "display")]
+use crate::config::{DisplayConfig, StopConfig};
+#[cfg(feature = "display")]
+use crate::departure::{collect_warnings, Departure};
+#[cfg(feature = "display")]
+use crate::font::{self, BdfFont, FontChoice, FontMetrics, TextMeasure};
+#[cfg(feature = "display")]
+use crate::layout;
+#[cfg(feature = "display")]
+use crate::pages::{Page, PageKind};
+#[cfg(feature = "display")]
+use crate::ring::RingDirection;
+#[cfg(feature = "display")]
+use crate::schedule::{self, DisplayLevel};
+#[cfg(feature = "display")]
+use crate::status::{Freshness, StatusLine, StatusPosition};
+#[cfg(feature = "display")]
+use crate::locale::Locale;
+#[cfg(feature = "display")]
+use crate::template::{FormatConfig, Template};
+#[cfg(feature = "display")]
+use crate::transition::{self, Transition, TransitionFrame};
+#[cfg(feature = "display")]
+use crate::urgency::Urgency;
+#[cfg(feature = "display")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "display")]
+use chrono_tz::Tz;
+#[cfg(feature = "display")]
+use std::borrow::Cow;
+#[cfg(feature = "display")]
+use std::cell::Cell;
+#[cfg(feature = "display")]
+use std::thread;
+#[cfg(feature = "display")]
+use std::time::{Duration, Instant};
 #[cfg(feature = "display")]
 use embedded_graphics::{
-    mono_font::{ascii::FONT_4X6, MonoTextStyle},
+    mono_font::{
+        iso_8859_1::{FONT_4X6, FONT_5X7, FONT_6X10},
+        MonoFont, MonoTextStyle,
+    },
     pixelcolor::Rgb888,
     prelude::*,
+    primitives::{CornerRadii, PrimitiveStyle, Rectangle, RoundedRectangle},
     text::Text,
 };
 #[cfg(feature = "display")]
-use rpi_led_matrix::{LedCanvas, LedColor, LedMatrix, LedMatrixOptions};
+use rpi_led_matrix::{LedCanvas, LedColor, LedFont, LedMatrix, LedMatrixOptions, LedRuntimeOptions};
+#[cfg(feature = "display")]
+use tracing::{info, debug, error, warn};
+
+// Left padding in pixels
+#[cfg(feature = "display")]
+const MARGIN: i32 = 2;
+
+// Disruption text, orange to stand out from the amber departures
+#[cfg(feature = "display")]
+const WARNING_COLOR: Rgb = Rgb::new(255, 100, 0);
+
+/// Canvas being drawn plus the rows left after the status bar
 #[cfg(feature = "display")]
-use tracing::{info, debug, error};
+struct Frame {
+    canvas: LedCanvas,
+    grid: layout::Grid,
+    width: i32,
+    height: i32,
+    max_width: u32,
+    /// Baseline of the status bar, drawn last so sliding pages don't cover it
+    status_y: Option<i32>,
+}
 
+/// Font ready for drawing
 #[cfg(feature = "display")]
-pub struct DisplayConfig {
-    /// Matrix width in pixels
-    pub width: u32,
-    /// Matrix height in pixels
-    pub height: u32,
-    /// Hardware mapping (e.g., "regular", "adafruit-hat", etc.)
-    pub hardware_mapping: String,
+enum LoadedFont {
+    /// Built-in Latin-1 font from embedded-graphics
+    Mono { font: &'static MonoFont<'static>, metrics: FontMetrics },
+    /// BDF font, rendered by rpi-rgb-led-matrix itself
+    Bdf { font: LedFont, metrics: BdfFont },
 }
 
 #[cfg(feature = "display")]
-impl Default for DisplayConfig {
-    fn default() -> Self {
-        Self {
-            width: 64,
-            height: 32,
-            hardware_mapping: "regular".to_string(),
+impl LoadedFont {
+    fn load(choice: &FontChoice) -> Result<Self, String> {
+        // FontChoice knows the built-in metrics without the display, make sure they match the font
+        let mono = |font: &'static MonoFont<'static>| {
+            let metrics = choice.builtin_metrics().expect("built-in font");
+            debug_assert_eq!(
+                metrics,
+                FontMetrics {
+                    char_width: font.character_size.width + font.character_spacing,
+                    height: font.character_size.height,
+                    baseline: font.baseline,
+                }
+            );
+            LoadedFont::Mono { font, metrics }
+        };
+
+        let font = match choice {
+            FontChoice::Font4x6 => mono(&FONT_4X6),
+            FontChoice::Font5x7 => mono(&FONT_5X7),
+            FontChoice::Font6x10 => mono(&FONT_6X10),
+            FontChoice::Bdf(path) => {
+                let metrics = BdfFont::load(path).map_err(|e| e.to_string())?;
+                let font = LedFont::new(path)
+                    .map_err(|e| format!("Failed to load font {}: {}", path.display(), e))?;
+                LoadedFont::Bdf { font, metrics }
+            }
+        };
+        Ok(font)
+    }
+
+    fn measure(&self) -> &dyn TextMeasure {
+        match self {
+            LoadedFont::Mono { metrics, .. } => metrics,
+            LoadedFont::Bdf { metrics, .. } => metrics,
+        }
+    }
+
+    fn metrics(&self) -> FontMetrics {
+        self.measure().metrics()
+    }
+
+    /// Text as it will be drawn: built-in fonts only cover Latin-1,
+    /// so everything else is simplified before measuring and drawing
+    fn prepare<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            LoadedFont::Mono { .. } => font::to_latin1(text),
+            LoadedFont::Bdf { .. } => Cow::Borrowed(text),
         }
     }
 }
@@ -37,7 +137,21 @@ impl Default for DisplayConfig {
 pub struct BvgDisplay {
     matrix: LedMatrix,
     config: DisplayConfig,
+    font: LoadedFont,
+    level: DisplayLevel,
+    status: Option<StatusLine>,
     current_index: usize,
+    /// Page and departure index on screen, the starting point of the next transition
+    shown: Option<(Page, usize)>,
+    /// Extra dimming for fade transitions, 255 = none
+    fade: Cell<u8>,
+    /// Off phase of the leave-now blinking
+    blink_off: bool,
+    /// Brightness the matrix was initialised with, the ceiling for software dimming
+    hardware_brightness: u8,
+    /// Minutes texts, `[format]` in the config
+    format: FormatConfig,
+    locale: Locale,
     canvas: Option<LedCanvas>,  // Reuse canvas to avoid memory leaks
 }
 
@@ -51,16 +165,49 @@ impl BvgDisplay {
         info!(
             width = config.width,
             height = config.height,
+            chain_length = config.chain_length,
+            parallel = config.parallel,
             hardware_mapping = %config.hardware_mapping,
+            pixel_mapper = ?config.pixel_mapper,
+            font = ?config.font,
             "Initializing display with config"
         );
 
+        config.validate().map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+
+        // Load the font first, a bad font path shouldn't touch the GPIOs
+        let font = LoadedFont::load(&config.font).map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+
         let mut options = LedMatrixOptions::new();
         options.set_cols(config.width);
         options.set_rows(config.height);
+        options.set_chain_length(config.chain_length);
+        options.set_parallel(config.parallel);
         options.set_hardware_mapping(&config.hardware_mapping);
+        options.set_scan_mode(config.scan_mode);
+        if let Some(mapper) = &config.pixel_mapper {
+            options.set_pixel_mapper_config(mapper);
+        }
+        options.set_pwm_bits(config.pwm_bits)
+            .map_err(|e| format!("Invalid pwm_bits {}: {}", config.pwm_bits, e))?;
+        options.set_brightness(config.brightness)
+            .map_err(|e| format!("Invalid brightness {}: {}", config.brightness, e))?;
+
+        let mut runtime_options = LedRuntimeOptions::new();
+        // The library would switch to "daemon" on its own, we drop to the configured user
+        // ("daemon" by default) right after init instead
+        runtime_options.set_drop_privileges(false);
+        if let Some(slowdown) = config.gpio_slowdown {
+            runtime_options.set_gpio_slowdown(slowdown);
+        }
 
-        let matrix = LedMatrix::new(Some(options), None)
+        let matrix = LedMatrix::new(Some(options), Some(runtime_options))
             .map_err(|e| {
                 let err_msg = format!("Failed to initialize LED matrix: {}", e);
                 error!("{}", err_msg);
@@ -71,15 +218,127 @@ impl BvgDisplay {
 
         Ok(Self { 
             matrix, 
+            level: DisplayLevel::On(config.brightness),
+            hardware_brightness: config.brightness,
             config,
+            font,
+            status: None,
             current_index: 0,
+            shown: None,
+            fade: Cell::new(255),
+            blink_off: false,
+            format: FormatConfig::default(),
+            locale: Locale::default(),
             canvas: None,  // Canvas will be created on first render
         })
     }
 
     /// Render departures to the LED matrix
-    /// Displays 1 departure on 3 lines with smart word wrapping
+    /// Displays 1 departure on 3 lines with smart word wrapping,
+    /// larger canvases show several departures stacked
     pub fn render_departures(&mut self, departures: &[Departure]) {
+        self.render_page(&Page::new(PageKind::Departure, 0), departures, || false);
+    }
+
+    /// Render one page of the rotation
+    /// Animates from the previous page if it has a transition and anything changed,
+    /// `interrupted` is asked between frames and cuts the animation short to the still page
+    pub fn render_page(&mut self, page: &Page, departures: &[Departure], interrupted: impl Fn() -> bool) {
+        let next = (page.clone(), self.current_index);
+        let previous = self.shown.replace(next.clone());
+
+        // Redraws of the same page (new data, status bar) are never animated
+        if let Some((old_page, old_index)) = previous.filter(|shown| *shown != next)
+            && page.transition != Transition::None
+            && self.level != DisplayLevel::Blank
+        {
+            let fps = self.config.transition_fps.max(1);
+            let frames = transition::frame_count(self.config.transition_ms, fps);
+            let (width, height) = self.dimensions();
+            debug!(transition = ?page.transition, frames, "Animating page change");
+
+            // The last frame is the still page below
+            // Frames are due at fixed times from the start, drawing doesn't stretch the animation
+            let frame_time = Duration::from_secs(1) / fps;
+            let started = Instant::now();
+            for i in 1..frames {
+                if interrupted() {
+                    break;
+                }
+                let progress = i as f32 / frames as f32;
+                let step = page.transition.frame(progress, width as i32, height as i32);
+                self.draw_frame(step, (&old_page, old_index), (page, next.1), departures);
+                let frame_deadline = started + frame_time * i;
+                thread::sleep(frame_deadline.saturating_duration_since(Instant::now()));
+            }
+        }
+
+        let still = Transition::None.frame(1.0, 0, 0);
+        self.draw_frame(still, (page, next.1), (page, next.1), departures);
+    }
+
+    /// Draw one frame with the old and/or new page as the transition says
+    fn draw_frame(
+        &mut self,
+        step: TransitionFrame,
+        old: (&Page, usize),
+        new: (&Page, usize),
+        departures: &[Departure],
+    ) {
+        let Some(mut frame) = self.begin_frame() else {
+            return;
+        };
+
+        for (layer, (page, index)) in [(step.old, old), (step.new, new)] {
+            let Some(layer) = layer else { continue };
+            let grid = frame.grid;
+            frame.grid.start_y += layer.y_offset;
+            self.fade.set(layer.fade);
+            self.draw_page(&mut frame, page, index, departures);
+            frame.grid = grid;
+        }
+        self.fade.set(255);
+
+        if let Some((start, end)) = step.blank_columns {
+            let bar = Rectangle::new(Point::new(start, 0), Size::new((end - start).max(0) as u32, frame.height as u32));
+            let _ = bar.into_styled(PrimitiveStyle::with_fill(Rgb888::BLACK)).draw(&mut frame.canvas);
+        }
+
+        if let (Some(y), Some(status)) = (frame.status_y, &self.status) {
+            // Clear the row first, a sliding page may have moved into it
+            let metrics = self.font.metrics();
+            let top_left = Point::new(0, y - metrics.baseline as i32 - 1);
+            let row = Rectangle::new(top_left, Size::new(frame.width as u32, metrics.height + 2));
+            let _ = row.into_styled(PrimitiveStyle::with_fill(Rgb888::BLACK)).draw(&mut frame.canvas);
+            self.draw_status_bar(&mut frame.canvas, status, y, frame.width);
+        }
+
+        self.finish_frame(frame.canvas);
+    }
+
+    fn draw_page(&self, frame: &mut Frame, page: &Page, index: usize, departures: &[Departure]) {
+        match page.kind {
+            PageKind::Departure => self.draw_departure_page(frame, index, departures),
+            PageKind::Board => self.draw_board_page(frame, departures),
+            PageKind::Disruptions => {
+                let warnings = collect_warnings(departures);
+                if warnings.is_empty() {
+                    self.draw_text_page(frame, self.locale.strings().no_disruptions, colors::AMBER);
+                } else {
+                    self.draw_text_page(frame, &warnings.join(" +++ "), WARNING_COLOR);
+                }
+            }
+            PageKind::Clock => self.draw_clock_page(frame, schedule::now_local()),
+            PageKind::Text => {
+                let text = page.text.as_deref().unwrap_or_default();
+                self.draw_text_page(frame, text, colors::AMBER);
+            }
+        }
+    }
+
+    /// Clear the reused canvas and reserve the status bar row
+    /// Returns None if the panel is blanked, the black frame is already shown then
+    fn begin_frame(&mut self) -> Option<Frame> {
         let is_new_canvas = self.canvas.is_none();
         
         // Reuse existing canvas or create one on first render
@@ -93,65 +352,117 @@ impl BvgDisplay {
             debug!("Canvas created for first render");
         }
         
-        let current_index = self.current_index % departures.len().max(1);
-        info!(
-            departure_index = current_index,
-            total_departures = departures.len(),
-            "Rendering departure {} of {}",
-            current_index + 1,
-            departures.len()
-        );
-        
         // Clear the canvas (black background)
         canvas.fill(&LedColor { red: 0, green: 0, blue: 0 });
 
-        // BVG yellow/amber color scheme
-        let text_color = LedColor {
-            red: 255,
-            green: 200,
-            blue: 0,
-        };
+        // Night mode: leave the canvas black
+        if self.level == DisplayLevel::Blank {
+            debug!("Display blanked");
+            self.finish_frame(canvas);
+            return None;
+        }
 
-        // Three-line format for one departure with smart wrapping
-        let line_height = 9;   // Height between lines
-        let start_y = 5;       // Top padding
-        let max_width = 16;    // Max chars per line
-
-        // Display current departure (cycling through list)
-        if let Some(departure) = departures.get(self.current_index) {
-            // Smart wrap: LINE + DESTINATION across multiple lines
-            let mut full_text = String::with_capacity(departure.line.len() + departure.destination.len() + 1);
-            full_text.push_str(&departure.line);
-            full_text.push(' ');
-            full_text.push_str(&departure.destination);
-            let lines = self.smart_wrap(&full_text, max_width, 2); // max 2 lines for destination
-            
-            // Draw destination lines (skip empty lines)
-            let mut last_line_index = 0;
-            for (i, line) in lines.iter().enumerate() {
-                if !line.is_empty() {
-                    let y_pos = start_y + (i as i32 * line_height);
-                    self.draw_text(&mut canvas, line, 2, y_pos, text_color);
-                    last_line_index = i;
-                }
-            }
-            
-            // Time on the next line after last destination line
-            let time_text = format!("{} min", departure.minutes);
-            let time_y = start_y + ((last_line_index + 1) as i32 * line_height);
-            self.draw_text(&mut canvas, &time_text, 2, time_y, text_color);
-            
-            drop(time_text);
-            drop(full_text);
-            drop(lines);
+        // Rows follow the font and the real canvas size (chains, mappers)
+        let (canvas_width, canvas_height) = canvas.canvas_size();
+        let mut grid = layout::Grid::new(canvas_height.max(0) as u32, self.font.metrics());
+
+        // Status bar takes one row off the page
+        let mut status_y = None;
+        if let (Some(bar), Some(_)) = (&self.config.status_bar, &self.status) {
+            let (bar_y, rest) = grid.reserve_row(bar.position == StatusPosition::Top);
+            status_y = Some(bar_y);
+            grid = rest;
         }
 
+        Some(Frame {
+            canvas,
+            grid,
+            width: canvas_width,
+            height: canvas_height,
+            max_width: (canvas_width - MARGIN).max(0) as u32,  // Max pixels per line
+            status_y,
+        })
+    }
+
+    fn finish_frame(&mut self, canvas: LedCanvas) {
         // Swap canvas to display - returns the old displayed canvas
         // Store it for reuse instead of dropping to prevent memory leaks
         let old_canvas = self.matrix.swap(canvas);
         debug!("Canvas swapped, displaying new content");
         self.canvas = Some(old_canvas);
     }
+
+    /// Current departure (cycling through list), plus the following
+    /// ones if the canvas has room for them
+    fn draw_departure_page(&self, frame: &mut Frame, index: usize, departures: &[Departure]) {
+        let current_index = index % departures.len().max(1);
+        debug!(
+            departure_index = current_index,
+            total_departures = departures.len(),
+            "Rendering departure {} of {}",
+            current_index + 1,
+            departures.len()
+        );
+
+        let per_screen = frame.grid.departures_per_screen().min(departures.len());
+        for slot in 0..per_screen {
+            let departure = &departures[(current_index + slot) % departures.len()];
+            let first_row = slot * frame.grid.rows_per_departure();
+            let grid = frame.grid;
+            self.draw_departure(&mut frame.canvas, departure, &grid, first_row, frame.max_width);
+        }
+    }
+
+    /// One departure per row: badge, destination, minutes right-aligned
+    fn draw_board_page(&self, frame: &mut Frame, departures: &[Departure]) {
+        let measure = self.font.measure();
+        let space = measure.char_width(' ') as i32;
+
+        for (row, departure) in departures.iter().take(frame.grid.rows).enumerate() {
+            let y = frame.grid.baseline(row);
+            let minutes = self.minutes_text(&self.format.board_minutes, departure);
+            let minutes = self.font.prepare(&minutes);
+            let minutes_x = frame.width - 1 - measure.text_width(&minutes) as i32;
+
+            let mut dest_x = self.draw_badge(&mut frame.canvas, departure, MARGIN, y) + space;
+            if let Some(ring) = departure.ring {
+                dest_x = self.draw_ring(&mut frame.canvas, ring, dest_x, y, colors::AMBER) + space;
+            }
+            let available = (minutes_x - space - dest_x).max(0) as u32;
+            let destination = layout::truncate(&self.font.prepare(&departure.destination), available, measure);
+
+            self.draw_text(&mut frame.canvas, &destination, dest_x, y, colors::AMBER);
+            if let Some(color) = self.minutes_color(departure) {
+                self.draw_text(&mut frame.canvas, &minutes, minutes_x, y, color);
+            }
+        }
+    }
+
+    /// Time in the middle, date below
+    fn draw_clock_page(&self, frame: &mut Frame, now: DateTime<Tz>) {
+        let time = now.format("%H:%M").to_string();
+        let date = now.format("%d.%m.%Y").to_string();
+        let lines: Vec<&str> = if frame.grid.rows >= 2 { vec![&time, &date] } else { vec![&time] };
+        self.draw_centered_lines(frame, &lines, colors::AMBER);
+    }
+
+    /// Free text wrapped over all rows, centred
+    fn draw_text_page(&self, frame: &mut Frame, text: &str, color: Rgb) {
+        let text = self.font.prepare(text);
+        let lines = layout::wrap(&text, frame.max_width, frame.grid.rows, self.font.measure());
+        let lines: Vec<&str> = lines.iter().map(String::as_str).filter(|l| !l.is_empty()).collect();
+        self.draw_centered_lines(frame, &lines, color);
+    }
+
+    /// Centre a block of lines horizontally and vertically in the grid
+    fn draw_centered_lines(&self, frame: &mut Frame, lines: &[&str], color: Rgb) {
+        let first_row = frame.grid.rows.saturating_sub(lines.len()) / 2;
+        for (i, line) in lines.iter().enumerate() {
+            let width = self.font.measure().text_width(line) as i32;
+            let x = ((frame.width - width) / 2).max(0);
+            self.draw_text(&mut frame.canvas, line, x, frame.grid.baseline(first_row + i), color);
+        }
+    }
     
     /// Move to next departure in the list (cycle)
     pub fn next_departure(&mut self, total: usize) {
@@ -167,76 +478,296 @@ impl BvgDisplay {
     pub fn current_index(&self) -> usize {
         self.current_index
     }
+
+    /// Advance the leave-now blinking, called from the main loop
+    /// Returns true if the screen needs a redraw
+    pub fn blink(&mut self, departures: &[Departure]) -> bool {
+        if !self.config.blink_leave_now {
+            return false;
+        }
+        let urgent = departures.iter().any(|d| d.urgency == Some(Urgency::LeaveNow));
+        if !urgent && !self.blink_off {
+            return false;
+        }
+        self.blink_off = urgent && !self.blink_off;
+        true
+    }
+
+    /// The minutes template, or just "cancelled" so the template's "'" doesn't trail it
+    fn minutes_text(&self, template: &Template, departure: &Departure) -> String {
+        let words = self.locale.words();
+        if departure.cancelled {
+            words.cancelled.to_string()
+        } else {
+            template.render(departure, &words)
+        }
+    }
+
+    /// Minutes colour by urgency, None while a leave-now departure blinks off
+    fn minutes_color(&self, departure: &Departure) -> Option<Rgb> {
+        if departure.cancelled {
+            return Some(WARNING_COLOR);
+        }
+        match departure.urgency {
+            None => Some(colors::AMBER),
+            Some(Urgency::LeaveNow) if self.blink_off => None,
+            Some(urgency) => Some(urgency.color()),
+        }
+    }
+
+    /// Templates and language of the texts, applies on the next render
+    pub fn set_format(&mut self, format: FormatConfig, locale: Locale) {
+        self.format = format;
+        self.locale = locale;
+    }
+
+    /// Refresh the status bar contents (clock, station, data age)
+    /// Returns true if anything visible changed and the screen needs a redraw
+    pub fn update_status(
+        &mut self,
+        stops: &[StopConfig],
+        now: DateTime<Tz>,
+        last_success: Option<DateTime<Utc>>,
+        interval: u64,
+    ) -> bool {
+        let Some(bar) = &self.config.status_bar else {
+            return false;
+        };
+
+        let status = StatusLine::new(bar, stops, now, last_success, interval);
+        if self.status.as_ref() == Some(&status) {
+            return false;
+        }
+        self.status = Some(status);
+        true
+    }
+
+    /// Apply the brightness schedule / night mode
+    /// Returns true if the level changed and the screen needs a redraw
+    pub fn set_level(&mut self, level: DisplayLevel) -> bool {
+        if self.level == level {
+            return false;
+        }
+        info!(old = ?self.level, new = ?level, "Display level changed");
+        self.level = level;
+        true
+    }
+
+    pub fn level(&self) -> DisplayLevel {
+        self.level
+    }
+
+    /// Scale a colour to the scheduled brightness and the current fade step
+    /// The hardware brightness is fixed at init (display.brightness), dimming below
+    /// that is done by scaling colours. Levels above it are capped.
+    fn dim(&self, color: Rgb) -> Rgb {
+        let percent = match self.level {
+            DisplayLevel::On(percent) => percent.min(self.config.brightness).min(self.hardware_brightness) as u32,
+            DisplayLevel::Blank => 0,
+        };
+        let base = self.hardware_brightness.max(1) as u32 * 255;
+        let percent = percent * self.fade.get() as u32;
+        let scale = |c: u8| (c as u32 * percent / base) as u8;
+        Rgb::new(scale(color.red), scale(color.green), scale(color.blue))
+    }
     
-    /// Smart word wrapping - breaks text by spaces to fit within max_width
-    fn smart_wrap(&self, text: &str, max_width: usize, max_lines: usize) -> Vec<String> {
-        // Use split_whitespace iterator directly to avoid intermediate Vec allocation
-        let mut words = text.split_whitespace();
-        let mut lines = Vec::with_capacity(max_lines);
-        let mut current_line = String::with_capacity(max_width);
-        
-        // Process words iterator directly
-        while let Some(word) = words.next() {
-            let test_len = if current_line.is_empty() {
-                word.len()
-            } else {
-                current_line.len() + 1 + word.len()
-            };
-            
-            if test_len <= max_width {
-                if !current_line.is_empty() {
-                    current_line.push(' ');
-                }
-                current_line.push_str(word);
-            } else {
-                // Current line is full, start new line
-                if !current_line.is_empty() {
-                    lines.push(std::mem::take(&mut current_line));
-                }
-                
-                if lines.len() >= max_lines {
-                    break;
+    /// Draw one departure starting at first_row:
+    /// line badge and destination (wrapped), then the minutes
+    fn draw_departure(
+        &self,
+        canvas: &mut LedCanvas,
+        departure: &Departure,
+        grid: &layout::Grid,
+        first_row: usize,
+        max_width: u32,
+    ) {
+        // BVG yellow/amber color scheme
+        let text_color = colors::AMBER;
+
+        // Smart wrap: LINE + DESTINATION across multiple lines
+        // All rows but the last one are for the destination
+        let dest_rows = grid.rows_per_departure().saturating_sub(1).max(1);
+        let mut full_text = String::with_capacity(departure.line.len() + departure.destination.len() + 1);
+        full_text.push_str(&departure.line);
+        full_text.push(' ');
+        full_text.push_str(&self.font.prepare(&departure.destination));
+        // Measured in pixels of the active font
+        let lines = layout::wrap(&full_text, max_width, dest_rows, self.font.measure());
+
+        // Draw destination lines (skip empty lines)
+        let mut last_line_index = 0;
+        for (i, line) in lines.iter().enumerate() {
+            if !line.is_empty() {
+                let y_pos = grid.baseline(first_row + i);
+                // First line starts with the line name, draw it as a coloured badge
+                let rest = if i == 0 { line.strip_prefix(departure.line.as_str()) } else { None };
+                match rest {
+                    Some(rest) => {
+                        let badge_end = self.draw_badge(canvas, departure, MARGIN, y_pos);
+                        self.draw_text(canvas, rest, badge_end, y_pos, text_color);
+                    }
+                    None => self.draw_text(canvas, line, MARGIN, y_pos, text_color),
                 }
-                
-                if word.len() > max_width {
-                    current_line = word.chars().take(max_width).collect();
-                } else {
-                    current_line = word.to_string();
+                last_line_index = i;
+            }
+        }
+
+        // Time on the next line after last destination line
+        let time_text = self.minutes_text(&self.format.departure_minutes, departure);
+        let time_y = grid.baseline(first_row + last_line_index + 1);
+        let mut time_x = MARGIN;
+        if let Some(ring) = departure.ring {
+            let space = self.font.measure().char_width(' ') as i32;
+            time_x = self.draw_ring(canvas, ring, time_x, time_y, text_color) + space;
+        }
+        if let Some(color) = self.minutes_color(departure) {
+            self.draw_text(canvas, &time_text, time_x, time_y, color);
+        }
+    }
+
+    /// Ringbahn arrow standing on the baseline, returns the x after it
+    fn draw_ring(&self, canvas: &mut LedCanvas, ring: RingDirection, x: i32, y: i32, color: Rgb) -> i32 {
+        let color = rgb888(self.dim(color));
+        let glyph = ring.glyph();
+        let top = y - glyph.len() as i32 + 1;
+        for (row, pixels) in glyph.iter().enumerate() {
+            for (column, pixel) in pixels.chars().enumerate() {
+                if pixel == '#' {
+                    let _ = Pixel(Point::new(x + column as i32, top + row as i32), color).draw(canvas);
                 }
             }
         }
-        
-        // Add remaining text
-        if !current_line.is_empty() && lines.len() < max_lines {
-            lines.push(current_line);
-        } else if current_line.is_empty() {
-            drop(current_line);
+        x + glyph[0].len() as i32
+    }
+
+    /// Clock on the left, data-age dot on the right, station name in between
+    fn draw_status_bar(&self, canvas: &mut LedCanvas, status: &StatusLine, y: i32, canvas_width: i32) {
+        let measure = self.font.measure();
+        let mut x = MARGIN;
+        let mut right = canvas_width - 1;
+
+        if let Some(freshness) = status.freshness {
+            let color = match freshness {
+                Freshness::Fresh => Rgb::new(0, 255, 0),
+                Freshness::Stale => Rgb::new(255, 255, 0),
+                Freshness::Offline => Rgb::new(255, 0, 0),
+            };
+            // 3x3 dot, vertically centred on the text
+            let top = y - self.font.metrics().baseline as i32 + 1;
+            let dot = Rectangle::new(Point::new(right - 3, top), Size::new(3, 3));
+            let _ = dot.into_styled(PrimitiveStyle::with_fill(rgb888(self.dim(color)))).draw(canvas);
+            right -= 5;
         }
-        
-        // Pad with empty lines if needed (avoid resize to prevent allocations)
-        while lines.len() < max_lines {
-            lines.push(String::new());
+
+        if let Some(clock) = &status.clock {
+            self.draw_text(canvas, clock, x, y, colors::AMBER);
+            x += (measure.text_width(clock) + measure.char_width(' ')) as i32;
         }
-        
-        lines
+
+        if let Some(station) = &status.station {
+            let available = (right - x).max(0) as u32;
+            let station = layout::truncate(&self.font.prepare(station), available, measure);
+            self.draw_text(canvas, &station, x, y, colors::WHITE);
+        }
+    }
+
+    /// Draw the line name as a badge in the line colour
+    /// Returns the x position right after the badge text
+    fn draw_badge(&self, canvas: &mut LedCanvas, departure: &Departure, x: i32, y: i32) -> i32 {
+        let color = colors::resolve_line_color(
+            &departure.line,
+            departure.color,
+            &self.config.line_colors,
+        );
+
+        // Box around the glyphs with 1px padding above and to the left
+        let metrics = self.font.metrics();
+        let text_width = self.font.measure().text_width(&departure.line) as i32;
+        let top_left = Point::new(x - 1, y - metrics.baseline as i32 - 1);
+        let size = Size::new(text_width as u32 + 1, metrics.height + 1);
+        let fill = PrimitiveStyle::with_fill(rgb888(self.dim(color.bg)));
+
+        let _ = match BadgeShape::for_line(&departure.line) {
+            BadgeShape::Rect => Rectangle::new(top_left, size).into_styled(fill).draw(canvas),
+            BadgeShape::Pill => {
+                RoundedRectangle::new(Rectangle::new(top_left, size), CornerRadii::new(Size::new(2, 2)))
+                    .into_styled(fill)
+                    .draw(canvas)
+            }
+        };
+
+        self.draw_text(canvas, &departure.line, x, y, color.fg);
+        x + text_width
     }
 
     /// Draw text on the canvas at specified position
-    fn draw_text(&self, canvas: &mut LedCanvas, text: &str, x: i32, y: i32, color: LedColor) {
-        // Convert LedColor to Rgb888 for embedded-graphics
-        let rgb_color = Rgb888::new(color.red, color.green, color.blue);
-        let style = MonoTextStyle::new(&FONT_4X6, rgb_color);
+    fn draw_text(&self, canvas: &mut LedCanvas, text: &str, x: i32, y: i32, color: Rgb) {
+        let color = self.dim(color);
+        let text = self.font.prepare(text);
+        match &self.font {
+            LoadedFont::Mono { font, .. } => {
+                let style = MonoTextStyle::new(font, rgb888(color));
 
-        // Create text with position and style
-        let text_drawable = Text::new(text, Point::new(x, y), style);
-        
-        // Draw to canvas (using embedded-graphics integration)
-        let _ = text_drawable.draw(canvas);
+                // Create text with position and style
+                let text_drawable = Text::new(&text, Point::new(x, y), style);
+
+                // Draw to canvas (using embedded-graphics integration)
+                let _ = text_drawable.draw(canvas);
+            }
+            LoadedFont::Bdf { font, .. } => {
+                // rpi-rgb-led-matrix draws BDF text at the baseline as well
+                let led_color = LedColor { red: color.red, green: color.green, blue: color.blue };
+                canvas.draw_text(font, &text, x, y, &led_color, 0, false);
+            }
+        }
+    }
+
+    /// Apply a reloaded config without touching the matrix
+    /// Layout, font, colours, status bar and brightness (up to the initial one) change live,
+    /// panel geometry and timing keep their running values.
+    pub fn reconfigure(&mut self, mut config: DisplayConfig) -> Result<(), String> {
+        config.validate()?;
+
+        if config.adopt_panel(&self.config) {
+            warn!("Panel size, chain, mapping and timing changes need a restart, keeping the running ones");
+        }
+        if config.brightness > self.hardware_brightness {
+            warn!(
+                brightness = config.brightness,
+                initial = self.hardware_brightness,
+                "Brightness above the initial value needs a restart, capping"
+            );
+        }
+        if config.font != self.config.font {
+            self.font = LoadedFont::load(&config.font)?;
+        }
+
+        if let DisplayLevel::On(_) = self.level {
+            self.level = DisplayLevel::On(config.brightness);
+        }
+        self.config = config;
+        // Status bar and page are rebuilt from the new config on the next render
+        self.status = None;
+        self.shown = None;
+        info!("Display config applied");
+        Ok(())
     }
 
+    /// Blank the panel, used on shutdown
+    pub fn clear(&mut self) {
+        let mut canvas = self.canvas.take().unwrap_or_else(|| self.matrix.offscreen_canvas());
+        canvas.fill(&LedColor { red: 0, green: 0, blue: 0 });
+        self.finish_frame(canvas);
+    }
+
+    /// Canvas size after chaining and pixel mapping
     pub fn dimensions(&self) -> (u32, u32) {
-        (self.config.width, self.config.height)
+        self.config.canvas_size()
     }
 }
 
-
+// Convert our colour type to Rgb888 for embedded-graphics
+#[cfg(feature = "display")]
+fn rgb888(color: Rgb) -> Rgb888 {
+    Rgb888::new(color.red, color.green, color.blue)
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Font used on the LED matrix
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FontChoice {
+    /// Built-in 4x6 bitmap font (fits 16 chars on a 64px panel)
+    #[default]
+    Font4x6,
+    /// Built-in 5x7 bitmap font
+    Font5x7,
+    /// Built-in 6x10 bitmap font, for larger panels
+    Font6x10,
+    /// BDF font loaded from disk, e.g. from rpi-rgb-led-matrix/fonts
+    Bdf(PathBuf),
+}
+
+impl FontChoice {
+    /// Parse "4x6", "5x7", "6x10" or a path to a .bdf file
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "4x6" => Ok(FontChoice::Font4x6),
+            "5x7" => Ok(FontChoice::Font5x7),
+            "6x10" => Ok(FontChoice::Font6x10),
+            path if path.ends_with(".bdf") => Ok(FontChoice::Bdf(PathBuf::from(path))),
+            other => Err(format!(
+                "unknown font '{}', expected 4x6, 5x7, 6x10 or a path to a .bdf file",
+                other
+            )),
+        }
+    }
+
+    /// Metrics of the built-in fonts (None for BDF, those have to be loaded first)
+    pub fn builtin_metrics(&self) -> Option<FontMetrics> {
+        match self {
+            FontChoice::Font4x6 => Some(FontMetrics { char_width: 4, height: 6, baseline: 4 }),
+            FontChoice::Font5x7 => Some(FontMetrics { char_width: 5, height: 7, baseline: 5 }),
+            FontChoice::Font6x10 => Some(FontMetrics { char_width: 6, height: 10, baseline: 7 }),
+            FontChoice::Bdf(_) => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FontChoice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        FontChoice::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Basic font dimensions in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FontMetrics {
+    /// Advance width of a character (the default one for proportional fonts)
+    pub char_width: u32,
+    /// Line height
+    pub height: u32,
+    /// Distance from the top of the line to the baseline
+    pub baseline: u32,
+}
+
+/// Measures rendered text width in pixels
+pub trait TextMeasure {
+    /// Advance width of a single character
+    fn char_width(&self, c: char) -> u32;
+
+    fn metrics(&self) -> FontMetrics;
+
+    fn text_width(&self, text: &str) -> u32 {
+        text.chars().map(|c| self.char_width(c)).sum()
+    }
+}
+
+// Monospace: every glyph has the same advance
+impl TextMeasure for FontMetrics {
+    fn char_width(&self, _c: char) -> u32 {
+        self.char_width
+    }
+
+    fn metrics(&self) -> FontMetrics {
+        *self
+    }
+}
+
+impl TextMeasure for BdfFont {
+    fn char_width(&self, c: char) -> u32 {
+        // Glyphs missing from the font are drawn with the default width
+        self.advances.get(&c).copied().unwrap_or(self.metrics.char_width)
+    }
+
+    fn metrics(&self) -> FontMetrics {
+        self.metrics
+    }
+}
+
+/// Glyph metrics of a BDF font
+/// Only what we need for layout, the bitmaps are rendered by rpi-rgb-led-matrix
+#[derive(Debug, Clone)]
+pub struct BdfFont {
+    pub metrics: FontMetrics,
+    /// Per-glyph advance width (DWIDTH)
+    pub advances: HashMap<char, u32>,
+}
+
+impl BdfFont {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read font {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("Invalid BDF font {}: {}", path.display(), e).into())
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut bbox: Option<(u32, u32, i32)> = None;  // width, height, y offset
+        let mut ascent: Option<u32> = None;
+        let mut advances = HashMap::new();
+        let mut encoding: Option<char> = None;
+
+        for line in text.lines() {
+            let mut parts = line.split_whitespace();
+            let keyword = parts.next().unwrap_or("");
+            let mut number = |name: &str| -> Result<i32, String> {
+                parts
+                    .next()
+                    .and_then(|v| v.parse::<i32>().ok())
+                    .ok_or_else(|| format!("bad {} line: '{}'", name, line))
+            };
+
+            match keyword {
+                "FONTBOUNDINGBOX" => {
+                    let width = number(keyword)?;
+                    let height = number(keyword)?;
+                    let _x_offset = number(keyword)?;
+                    let y_offset = number(keyword)?;
+                    bbox = Some((width.max(0) as u32, height.max(0) as u32, y_offset));
+                }
+                "FONT_ASCENT" => ascent = Some(number(keyword)?.max(0) as u32),
+                // Negative encodings mark glyphs without a code point
+                "ENCODING" => encoding = u32::try_from(number(keyword)?).ok().and_then(char::from_u32),
+                "DWIDTH" => {
+                    if let Some(c) = encoding {
+                        advances.insert(c, number(keyword)?.max(0) as u32);
+                    }
+                }
+                "ENDCHAR" => encoding = None,
+                _ => {}
+            }
+        }
+
+        let (width, height, y_offset) = bbox.ok_or("missing FONTBOUNDINGBOX")?;
+        // FONT_ASCENT is optional, derive it from the bounding box otherwise
+        let baseline = ascent.unwrap_or_else(|| (height as i32 + y_offset).max(0) as u32);
+
+        Ok(Self {
+            metrics: FontMetrics { char_width: width, height, baseline },
+            advances,
+        })
+    }
+}
+
+/// Map text onto the Latin-1 range the built-in fonts cover
+/// German umlauts and ß are kept, typographic punctuation is simplified,
+/// anything else becomes '?' instead of a missing glyph
+pub fn to_latin1(text: &str) -> Cow<'_, str> {
+    if text.chars().all(|c| (c as u32) < 0x100) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            c if (c as u32) < 0x100 => result.push(c),
+            '‐' | '‑' | '‒' | '–' | '—' => result.push('-'),
+            '‘' | '’' | '‚' => result.push('\''),
+            '“' | '”' | '„' => result.push('"'),
+            '…' => result.push_str("..."),
+            'ẞ' => result.push_str("SS"),
+            _ => result.push('?'),
+        }
+    }
+    Cow::Owned(result)
+}
@@ -0,0 +1,60 @@
+use crate::control::{self, Command, Control, HttpConfig, Request, SharedState};
+use chrono::Utc;
+use std::sync::mpsc::Sender;
+use std::thread::{self, JoinHandle};
+use tiny_http::{Header, Response, Server};
+use tracing::{debug, info, warn};
+
+/// Serve the status and control API on its own thread
+/// Routing lives in control.rs, this only moves bytes
+/// Without `commands` (no display) only the read-only routes exist
+pub fn spawn(
+    config: &HttpConfig,
+    state: SharedState,
+    commands: Option<Sender<Command>>,
+) -> Result<JoinHandle<()>, String> {
+    let server = Server::http(&config.listen)
+        .map_err(|e| format!("Failed to listen on {}: {}", config.listen, e))?;
+    info!(listen = %config.listen, "HTTP API listening");
+    if commands.is_some() && config.is_unprotected() {
+        warn!(listen = %config.listen, "Anyone on the network can control the board, set [http] token");
+    }
+    let control = Control::new(commands, config.token.clone());
+
+    thread::Builder::new()
+        .name("http".to_string())
+        .spawn(move || {
+            for mut request in server.incoming_requests() {
+                let method = request.method().to_string();
+                let response = match control::read_body(request.as_reader()) {
+                    Ok(body) => {
+                        let authorization = request
+                            .headers()
+                            .iter()
+                            .find(|header| header.field.equiv("Authorization"))
+                            .map(|header| header.value.to_string());
+                        let mut http_request = Request::new(&method, request.url(), &body);
+                        if let Some(authorization) = &authorization {
+                            http_request = http_request.with_authorization(authorization);
+                        }
+                        control::handle(&http_request, &state, &control, Utc::now())
+                    }
+                    Err(response) => {
+                        warn!(method = %method, url = %request.url(), status = response.status, "Rejected request body");
+                        response
+                    }
+                };
+                debug!(method = %method, url = %request.url(), status = response.status, "HTTP request");
+
+                let content_type = Header::from_bytes("Content-Type", response.content_type)
+                    .expect("static header is valid");
+                let reply = Response::from_string(response.body)
+                    .with_status_code(response.status)
+                    .with_header(content_type);
+                if let Err(e) = request.respond(reply) {
+                    warn!("Failed to send HTTP response: {}", e);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to start HTTP thread: {}", e))
+}
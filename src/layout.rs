@@ -0,0 +1,153 @@
+use crate::font::{FontMetrics, TextMeasure};
+
+/// Appended to text that had to be cut off
+/// Two dots instead of '…', the built-in fonts have no glyph for it
+pub const ELLIPSIS: &str = "..";
+
+/// Cut text to fit into max_width pixels, adding an ellipsis if anything was cut
+pub fn truncate(text: &str, max_width: u32, font: &dyn TextMeasure) -> String {
+    if font.text_width(text) <= max_width {
+        return text.to_string();
+    }
+
+    let ellipsis_width = font.text_width(ELLIPSIS);
+    if ellipsis_width > max_width {
+        // Not even the ellipsis fits, just cut hard
+        return take_width(text, max_width, font).to_string();
+    }
+
+    let mut result = take_width(text, max_width - ellipsis_width, font).trim_end().to_string();
+    result.push_str(ELLIPSIS);
+    result
+}
+
+/// Longest prefix of text that fits into max_width pixels
+fn take_width<'a>(text: &'a str, max_width: u32, font: &dyn TextMeasure) -> &'a str {
+    let mut width = 0;
+    for (i, c) in text.char_indices() {
+        width += font.char_width(c);
+        if width > max_width {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// Word wrapping by rendered width
+/// Breaks text at spaces into at most max_lines lines of max_width pixels.
+/// Words wider than a line and text that doesn't fit into the last line are
+/// truncated with an ellipsis. The result is padded with empty lines to max_lines.
+pub fn wrap(text: &str, max_width: u32, max_lines: usize, font: &dyn TextMeasure) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::with_capacity(max_lines);
+    if max_lines == 0 {
+        return lines;
+    }
+
+    let space_width = font.char_width(' ');
+    let mut current_line = String::new();
+    let mut current_width = 0;
+    let mut overflow = false;
+
+    for word in text.split_whitespace() {
+        let word_width = font.text_width(word);
+        let needed = if current_line.is_empty() { word_width } else { current_width + space_width + word_width };
+
+        if needed <= max_width {
+            if !current_line.is_empty() {
+                current_line.push(' ');
+            }
+            current_line.push_str(word);
+            current_width = needed;
+            continue;
+        }
+
+        // Current line is full, start new line
+        if !current_line.is_empty() {
+            if lines.len() + 1 == max_lines {
+                // No line left for this word
+                overflow = true;
+                break;
+            }
+            lines.push(std::mem::take(&mut current_line));
+        }
+
+        current_line = truncate(word, max_width, font);
+        current_width = font.text_width(&current_line);
+    }
+
+    if overflow {
+        // Mark that the text goes on
+        let mut last = current_line;
+        last.push_str(ELLIPSIS);
+        current_line = truncate(&last, max_width, font);
+    }
+
+    if !current_line.is_empty() {
+        lines.push(current_line);
+    }
+
+    // Pad with empty lines so callers can index by line number
+    while lines.len() < max_lines {
+        lines.push(String::new());
+    }
+
+    lines
+}
+
+/// Text rows on the canvas for a given font
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grid {
+    /// Distance between two baselines
+    pub line_height: i32,
+    /// Baseline of the first row
+    pub start_y: i32,
+    /// Number of rows that fit completely
+    pub rows: usize,
+}
+
+impl Grid {
+    /// 3px between lines and 1px top padding, with 4x6 that gives the classic
+    /// three rows at y = 5, 14, 23 on a 32px panel
+    pub fn new(canvas_height: u32, font: FontMetrics) -> Self {
+        let line_height = font.height + 3;
+        let rows = if canvas_height < font.height + 1 {
+            0
+        } else {
+            // The last row only needs the glyph height, not the spacing
+            ((canvas_height - font.height - 1) / line_height + 1) as usize
+        };
+
+        Self {
+            line_height: line_height as i32,
+            start_y: font.baseline as i32 + 1,
+            rows,
+        }
+    }
+
+    /// Baseline y of a row
+    pub fn baseline(&self, row: usize) -> i32 {
+        self.start_y + row as i32 * self.line_height
+    }
+
+    /// How many departures fit on one screen
+    /// Each departure takes up to three rows (two for the destination, one for the time)
+    pub fn departures_per_screen(&self) -> usize {
+        (self.rows / self.rows_per_departure()).max(1)
+    }
+
+    pub fn rows_per_departure(&self) -> usize {
+        self.rows.clamp(1, 3)
+    }
+
+    /// Take one row off the top or bottom, e.g. for the status bar
+    /// Returns the baseline of the reserved row and the grid that is left
+    pub fn reserve_row(&self, at_top: bool) -> (i32, Grid) {
+        let rest = Grid {
+            line_height: self.line_height,
+            start_y: if at_top { self.start_y + self.line_height } else { self.start_y },
+            rows: self.rows.saturating_sub(1),
+        };
+        let row = if at_top { 0 } else { self.rows.saturating_sub(1) };
+        (self.baseline(row), rest)
+    }
+}
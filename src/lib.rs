@@ -1,13 +1,47 @@
 pub mod departure;
+pub mod destination;
 pub mod api;
+pub mod cache;
+pub mod colors;
+pub mod config;
+pub mod control;
+pub mod font;
+pub mod layout;
+pub mod line;
+pub mod locale;
+pub mod metrics;
+pub mod mqtt;
+pub mod output;
+pub mod pages;
+pub mod privileges;
+pub mod ring;
+pub mod schedule;
+pub mod signals;
+pub mod status;
+pub mod systemd;
+pub mod template;
+pub mod transition;
+pub mod tui;
+pub mod urgency;
 
 #[cfg(feature = "display")]
 pub mod display;
 
-pub use departure::{Departure, get_mock_departures};
-pub use api::{fetch_departures, fetch_warschauer_str};
+#[cfg(feature = "http")]
+pub mod http;
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt_client;
+
+#[cfg(feature = "tui")]
+pub mod tui_app;
+
+pub use departure::{Departure, collect_warnings, get_mock_departures};
+pub use api::{fetch_arrivals, fetch_departures, fetch_stops, fetch_stops_with, fetch_warschauer_str};
+pub use colors::{LineColor, Rgb};
+pub use config::{BoardMode, Config, DisplayConfig, FetchConfig, StopConfig};
 
 #[cfg(feature = "display")]
-pub use display::{BvgDisplay, DisplayConfig};
+pub use display::BvgDisplay;
 
 
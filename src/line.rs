@@ -0,0 +1,75 @@
+use crate::departure::Departure;
+use crate::template::{Template, Words};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// `rusty-bvg line` for status bars, `[line]` in the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LineConfig {
+    /// Per departure, see `template::Template`
+    pub template: Template,
+    pub separator: String,
+    /// Departures on the line at most
+    pub count: usize,
+    /// Longest line in characters, 0 = no limit
+    pub max_chars: usize,
+    /// Printed when there are no departures
+    pub empty: String,
+    /// Seconds a fetched board is reused before asking the API again
+    pub max_age: u64,
+    /// Defaults to a per-user file in $XDG_RUNTIME_DIR or /tmp
+    pub cache: Option<PathBuf>,
+}
+
+impl Default for LineConfig {
+    fn default() -> Self {
+        Self {
+            template: Template::parse("{line} {min}'").expect("valid template"),
+            separator: " · ".to_string(),
+            count: 3,
+            max_chars: 0,
+            empty: String::new(),
+            max_age: 30,
+            cache: None,
+        }
+    }
+}
+
+/// "U1 3' · S5 7' · M10 9'"
+/// Departures that don't fit max_chars are left out, a single one is truncated
+pub fn render(departures: &[Departure], config: &LineConfig, words: &Words) -> String {
+    let mut line = String::new();
+    for (i, departure) in departures.iter().take(config.count).enumerate() {
+        let entry = departure.format_with(&config.template, words);
+        let separator = if i == 0 { "" } else { config.separator.as_str() };
+        let fits = config.max_chars == 0
+            || line.chars().count() + separator.chars().count() + entry.chars().count() <= config.max_chars;
+
+        if fits {
+            line.push_str(separator);
+            line.push_str(&entry);
+        } else {
+            if i == 0 {
+                line = entry.chars().take(config.max_chars).collect();
+            }
+            break;
+        }
+    }
+
+    if line.is_empty() { config.empty.clone() } else { line }
+}
+
+/// `rusty-bvg [options] line [options]`: `line` is the first argument that isn't an option
+/// or the value of `--config`/`--output`
+pub fn is_line_command(args: impl IntoIterator<Item = String>) -> bool {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--config" || arg == "--output" {
+            args.next();
+        } else if !arg.starts_with("--") {
+            return arg == "line";
+        }
+    }
+    false
+}
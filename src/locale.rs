@@ -0,0 +1,99 @@
+use crate::template::Words;
+use serde::Deserialize;
+
+/// Language of everything riders read, `locale` in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+/// User-visible texts of one language
+#[derive(Debug, PartialEq, Eq)]
+pub struct Strings {
+    /// Due departures instead of the minutes
+    pub now: &'static str,
+    /// Unit after the minutes
+    pub minutes: &'static str,
+    /// Cancelled departures instead of the minutes
+    pub cancelled: &'static str,
+    pub no_disruptions: &'static str,
+    pub no_departures: &'static str,
+    pub waiting: &'static str,
+    pub fetching: &'static str,
+    pub all_stops: &'static str,
+    /// Board title in arrivals mode
+    pub arrivals: &'static str,
+    pub line: &'static str,
+    pub destination: &'static str,
+    /// Minutes column header
+    pub min: &'static str,
+    pub delay: &'static str,
+    pub remarks: &'static str,
+    pub log: &'static str,
+    /// Key help of the terminal board
+    pub keys: &'static str,
+}
+
+const EN: Strings = Strings {
+    now: "now",
+    minutes: "min",
+    cancelled: "cancelled",
+    no_disruptions: "No disruptions",
+    no_departures: "No departures",
+    waiting: "Waiting for data…",
+    fetching: "fetching…",
+    all_stops: "All stops",
+    arrivals: "Arrivals",
+    line: "Line",
+    destination: "Destination",
+    min: "Min",
+    delay: "Delay",
+    remarks: "Remarks",
+    log: "Log",
+    keys: "q quit  ←/→ stop  u/s/t U-Bahn/S-Bahn/Tram  r refresh",
+};
+
+const DE: Strings = Strings {
+    now: "sofort",
+    minutes: "Min.",
+    cancelled: "fällt aus",
+    no_disruptions: "Keine Störungen",
+    no_departures: "Keine Abfahrten",
+    waiting: "Warte auf Daten…",
+    fetching: "lade…",
+    all_stops: "Alle Haltestellen",
+    arrivals: "Ankünfte",
+    line: "Linie",
+    destination: "Ziel",
+    min: "Min.",
+    delay: "Verspätung",
+    remarks: "Hinweise",
+    log: "Protokoll",
+    keys: "q beenden  ←/→ Haltestelle  u/s/t U-Bahn/S-Bahn/Tram  r aktualisieren",
+};
+
+impl Locale {
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Locale::En => &EN,
+            Locale::De => &DE,
+        }
+    }
+
+    /// Age of the shown data, "updated 12s ago"
+    pub fn updated_ago(self, seconds: i64) -> String {
+        match self {
+            Locale::En => format!("updated {}s ago", seconds),
+            Locale::De => format!("vor {}s aktualisiert", seconds),
+        }
+    }
+
+    /// What the template fields fill in
+    pub fn words(self) -> Words<'static> {
+        let strings = self.strings();
+        Words { now: strings.now, minutes: strings.minutes, cancelled: strings.cancelled }
+    }
+}
@@ -1,12 +1,37 @@
-use rusty_bvg::fetch_warschauer_str;
-#[allow(unused_imports)]
+use rusty_bvg::fetch_stops_with;
 use rusty_bvg::Departure;
-use std::thread;
-use std::time::Duration;
+use rusty_bvg::Config;
+use rusty_bvg::config::ConfigWatcher;
+use rusty_bvg::control::{BoardState, Command, SharedState};
+use rusty_bvg::schedule;
+use rusty_bvg::signals::Signals;
+use rusty_bvg::systemd::{self, Systemd};
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{error, info, warn, debug};
 
 #[cfg(feature = "display")]
 use rusty_bvg::BvgDisplay;
+#[cfg(not(feature = "display"))]
+use rusty_bvg::output::{self, OutputFormat};
+#[cfg(not(feature = "display"))]
+use std::io::Write;
+#[cfg(feature = "display")]
+use rusty_bvg::collect_warnings;
+#[cfg(feature = "display")]
+use rusty_bvg::privileges;
+#[cfg(feature = "display")]
+use rusty_bvg::pages::{Page, PageContext, PageKind, PageScheduler};
+#[cfg(feature = "display")]
+use rusty_bvg::schedule::DisplayLevel;
+#[cfg(feature = "display")]
+use chrono::DateTime;
+#[cfg(feature = "display")]
+use std::sync::mpsc;
+use chrono::Utc;
 
 
 fn init_logging() {
@@ -37,6 +62,297 @@ fn init_logging() {
         .init();
 }
 
+/// Logs for the log pane of the terminal UI, stderr would garble the screen
+#[cfg(all(feature = "tui", not(feature = "display")))]
+fn init_tui_logging(logs: rusty_bvg::tui::LogBuffer) {
+    use tracing_subscriber::fmt;
+    use tracing_subscriber::prelude::*;
+    use tracing_subscriber::EnvFilter;
+
+    // The pane is narrow, the time is enough of a timestamp
+    let layer = fmt::layer()
+        .with_writer(move || logs.clone())
+        .with_ansi(false)
+        .with_target(false)
+        .with_timer(fmt::time::ChronoLocal::new("%H:%M:%S".to_string()));
+
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layer)
+        .init();
+}
+
+// Used when neither --config nor RUSTY_BVG_CONFIG is given
+const DEFAULT_CONFIG_PATH: &str = "/etc/rusty-bvg.toml";
+
+/// Find the config file: `--config <path>`, then $RUSTY_BVG_CONFIG, then the default path
+fn config_path() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    if let Ok(path) = std::env::var("RUSTY_BVG_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    let default = PathBuf::from(DEFAULT_CONFIG_PATH);
+    default.exists().then_some(default)
+}
+
+/// Console output: `--output <format>`, log lines only without it
+#[cfg(not(feature = "display"))]
+fn output_format() -> OutputFormat {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = if arg == "--output" {
+            args.next().unwrap_or_default()
+        } else if let Some(value) = arg.strip_prefix("--output=") {
+            value.to_string()
+        } else {
+            continue;
+        };
+        return match value.parse() {
+            Ok(format) => format,
+            Err(e) => {
+                error!("{}", e);
+                std::process::exit(2);
+            }
+        };
+    }
+    OutputFormat::Log
+}
+
+/// `rusty-bvg line`: print one line for a status bar and exit
+/// No logging, panels show stdout and errors go to stderr
+#[cfg(not(feature = "display"))]
+fn run_line() -> i32 {
+    use rusty_bvg::cache::{self, Cache};
+    use rusty_bvg::fetch_stops;
+
+    let config = match config_path().map(|path| Config::load(&path)) {
+        Some(Ok(config)) => config,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+        None => Config::default(),
+    };
+    let path = config.line.cache.clone().unwrap_or_else(cache::default_path);
+    let now = Utc::now();
+    let cached = Cache::load(&path);
+
+    let departures = match cached.as_ref() {
+        Some(cached) if cached.is_fresh(&config.stops, &config.fetch, now, Duration::from_secs(config.line.max_age)) => {
+            cached.departures_at(now, &config.stops)
+        }
+        _ => {
+            let agent = ureq::AgentBuilder::new()
+                .timeout(std::time::Duration::from_secs(10))
+                .build();
+            match fetch_stops(&agent, &config.stops, &config.fetch) {
+                Ok(departures) => {
+                    if let Err(e) = Cache::new(&config.stops, &config.fetch, departures.clone(), now).save(&path) {
+                        eprintln!("Cache {} not written: {}", path.display(), e);
+                    }
+                    departures
+                }
+                // Better an older board than none, minutes are counted down
+                Err(e) => match cached.filter(|c| c.is_fresh(&config.stops, &config.fetch, now, cache::STALE_MAX_AGE)) {
+                    Some(cached) => cached.departures_at(now, &config.stops),
+                    None => {
+                        eprintln!("{}", e);
+                        return 1;
+                    }
+                },
+            }
+        }
+    };
+
+    println!("{}", rusty_bvg::line::render(&departures, &config.line, &config.locale.words()));
+    0
+}
+
+/// `--tui`: full-screen terminal board instead of log lines
+#[cfg(not(feature = "display"))]
+fn tui_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--tui")
+}
+
+/// Terminal board, runs until the user quits
+/// Errors before and after the UI go to stderr, there is no log pane then
+#[cfg(all(feature = "tui", not(feature = "display")))]
+fn run_tui() {
+    let config = match config_path() {
+        Some(path) => Config::load(&path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }),
+        None => Config::default(),
+    };
+
+    let logs = rusty_bvg::tui::LogBuffer::default();
+    init_tui_logging(logs.clone());
+    let signals = register_signals();
+
+    if let Err(e) = rusty_bvg::tui_app::run(&config, logs, &signals) {
+        eprintln!("Terminal UI failed: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(all(not(feature = "tui"), not(feature = "display")))]
+fn run_tui() {
+    eprintln!("--tui needs a build with `--features tui`");
+    std::process::exit(2);
+}
+
+/// Configured stops for the startup banner, by name where one is set
+fn stop_names(config: &Config) -> String {
+    let names: Vec<&str> = config.stops.iter().map(|s| s.name.as_deref().unwrap_or(&s.id)).collect();
+    names.join(", ")
+}
+
+fn banner(title: &str) {
+    info!("{}", title);
+    info!("{}", "=".repeat(title.chars().count()));
+}
+
+/// Load config, falling back to defaults when there is no config file
+/// A config file that exists but can't be parsed is fatal
+fn load_config() -> Config {
+    let Some(path) = config_path() else {
+        info!("No config file found, using defaults");
+        return Config::default();
+    };
+
+    match Config::load(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Re-read the config file after SIGHUP or when it was written
+/// A broken file keeps the running config, a typo shouldn't take the board down
+fn reload_config(config: &mut Config, systemd: &Systemd) -> bool {
+    let Some(path) = config_path() else {
+        warn!("Reload requested, but there is no config file");
+        return false;
+    };
+
+    systemd.reloading();
+    let result = Config::load(&path);
+    systemd.ready();
+
+    match result {
+        Ok(new_config) => {
+            info!("Config reloaded");
+            *config = new_config;
+            true
+        }
+        Err(e) => {
+            error!("Config reload failed, keeping the old config: {}", e);
+            false
+        }
+    }
+}
+
+/// Last fetch outcome as systemd STATUS (shown by `systemctl status`) and for the HTTP API
+fn report_fetch(
+    systemd: &Systemd,
+    state: &SharedState,
+    result: &Result<Vec<Departure>, Box<dyn Error>>,
+    started: Instant,
+) {
+    systemd.status(&systemd::fetch_status(result, schedule::now_local()));
+    state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .record_fetch(result, Utc::now(), started.elapsed());
+}
+
+/// Start the HTTP API if `[http]` is configured
+/// Runs until the process exits, changing [http] needs a restart
+fn start_http(config: &Config, state: &SharedState, commands: Option<Sender<Command>>) {
+    let Some(http) = &config.http else {
+        return;
+    };
+
+    #[cfg(feature = "http")]
+    if let Err(e) = rusty_bvg::http::spawn(http, Arc::clone(state), commands) {
+        error!("{}", e);
+    }
+
+    #[cfg(not(feature = "http"))]
+    {
+        let _ = (state, commands);
+        warn!(listen = %http.listen, "[http] is configured, but this build has no http feature");
+    }
+}
+
+/// MQTT output, only connected if built with the mqtt feature and `[mqtt]` is configured
+/// Changing [mqtt] needs a restart
+struct MqttOutput {
+    #[cfg(feature = "mqtt")]
+    client: Option<rusty_bvg::mqtt_client::MqttClient>,
+}
+
+impl MqttOutput {
+    fn start(config: &Config, commands: Option<Sender<Command>>) -> Self {
+        #[cfg(feature = "mqtt")]
+        {
+            let client = config.mqtt.as_ref().and_then(|mqtt| {
+                rusty_bvg::mqtt_client::MqttClient::connect(mqtt, commands)
+                    .map_err(|e| error!("{}", e))
+                    .ok()
+            });
+            Self { client }
+        }
+
+        #[cfg(not(feature = "mqtt"))]
+        {
+            let _ = commands;
+            if let Some(mqtt) = &config.mqtt {
+                warn!(host = %mqtt.host, "[mqtt] is configured, but this build has no mqtt feature");
+            }
+            Self {}
+        }
+    }
+
+    /// The same filtered and cleaned departures the board uses
+    fn publish(&mut self, config: &Config, result: &Result<Vec<Departure>, Box<dyn Error>>) {
+        #[cfg(feature = "mqtt")]
+        if let (Some(client), Ok(departures)) = (&mut self.client, result) {
+            client.publish_board(&config.stops, departures);
+        }
+
+        #[cfg(not(feature = "mqtt"))]
+        let _ = (config, result);
+    }
+}
+
+/// SIGINT/SIGTERM stop the main loop, SIGHUP reloads the config
+fn register_signals() -> Signals {
+    match Signals::register() {
+        Ok(signals) => signals,
+        Err(e) => {
+            error!("Failed to register signal handlers: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Set up panic handler to log panics before crashing
 fn setup_panic_handler() {
     std::panic::set_hook(Box::new(|panic_info| {
@@ -73,15 +389,39 @@ fn setup_panic_handler() {
 // API test mode (without LED matrix)
 #[cfg(not(feature = "display"))]
 fn main() {
+    if rusty_bvg::line::is_line_command(std::env::args().skip(1)) {
+        std::process::exit(run_line());
+    }
+
+    // Before the panic handler: ratatui restores the terminal on panic,
+    // then the default hook prints to the normal screen
+    if tui_requested() {
+        run_tui();
+        return;
+    }
+
     setup_panic_handler();
     init_logging();
 
-    info!("BVG API Test Mode - Warschauer Straße");
-    info!("======================================");
+    let output_format = output_format();
+    let mut config = load_config();
+
+    banner(&format!("BVG API Test Mode - {}", stop_names(&config)));
     info!("(Display mode disabled - run with --features display on RPi)");
+    let signals = register_signals();
+    let mut systemd = Systemd::new();
+
+    let state: SharedState = Arc::new(Mutex::new(BoardState {
+        fetch_interval: config.fetch.interval,
+        ..BoardState::default()
+    }));
+    // Nothing to control without a display, only the read-only API
+    start_http(&config, &state, None);
+    let mut mqtt = MqttOutput::start(&config, None);
 
     info!("API ready");
-    info!("Fetching departures every 20 seconds...");
+    systemd.ready();
+    info!("Fetching departures every {} seconds...", config.fetch.interval);
     info!("Press Ctrl+C to exit");
 
     let agent = ureq::AgentBuilder::new()
@@ -91,8 +431,41 @@ fn main() {
     #[cfg(debug_assertions)]
     let mut last_departures: Vec<Departure> = Vec::new();
 
-    loop {
-        match fetch_warschauer_str(&agent) {
+    let mut watcher = ConfigWatcher::new(config_path());
+    let mut last_fetch: Option<Instant> = None;
+
+    while !signals.shutdown_requested() {
+        systemd.watchdog();
+
+        // SIGHUP or a saved config file, both checked so neither is left pending
+        if (signals.take_reload() | watcher.changed()) && reload_config(&mut config, &systemd) {
+            state.lock().unwrap_or_else(|e| e.into_inner()).fetch_interval = config.fetch.interval;
+            last_fetch = None;
+        }
+
+        let fetch_due = last_fetch.is_none_or(|t| t.elapsed() >= Duration::from_secs(config.fetch.interval));
+        if !fetch_due {
+            signals.sleep(Duration::from_secs(1));
+            continue;
+        }
+        last_fetch = Some(Instant::now());
+
+        let started = Instant::now();
+        let result = fetch_stops_with(&agent, &config.stops, &config.fetch, || systemd.watchdog());
+        report_fetch(&systemd, &state, &result, started);
+        mqtt.publish(&config, &result);
+
+        // Boards on stdout for scripts, errors only go to the log
+        if let Ok(departures) = &result
+            && let Some(board) = output::render(output_format, departures, Utc::now(), &config.format.departure, config.locale)
+            && let Err(e) = std::io::stdout().lock().write_all(board.as_bytes())
+        {
+            // Reader gone, e.g. `| head`
+            info!("Stdout closed ({}), exiting", e);
+            break;
+        }
+
+        match result {
             Ok(departures) => {
                 if !departures.is_empty() {
                     info!("Fetched {} departures", departures.len());
@@ -118,8 +491,11 @@ fn main() {
             }
         }
 
-        thread::sleep(Duration::from_secs(20));
+        signals.sleep(Duration::from_secs(1));
     }
+
+    info!("Shutting down");
+    systemd.stopping();
 }
 
 // Full mode with LED display (RPi)
@@ -128,8 +504,16 @@ fn main() {
     setup_panic_handler();
     init_logging();
 
-    info!("BVG Live Display - Warschauer Straße");
-    info!("=====================================");
+    let mut config = load_config();
+    banner(&format!("BVG Live Display - {}", stop_names(&config)));
+    let signals = register_signals();
+    let mut systemd = Systemd::new();
+
+    let state: SharedState = Arc::new(Mutex::new(BoardState {
+        fetch_interval: config.fetch.interval,
+        ..BoardState::default()
+    }));
+    let (command_tx, commands) = mpsc::channel();
 
     info!("API ready");
 
@@ -137,8 +521,10 @@ fn main() {
         .timeout(std::time::Duration::from_secs(10))
         .build();
 
+    let mut pages = PageScheduler::new(config.display.pages.clone(), Instant::now());
+
     // Initialize display
-    let mut display = match BvgDisplay::new() {
+    let mut display = match BvgDisplay::with_config(config.display.clone()) {
         Ok(d) => {
             let (width, height) = d.dimensions();
             info!("Display initialized: {}x{}", width, height);
@@ -151,25 +537,49 @@ fn main() {
         }
     };
 
+    display.set_format(config.format.clone(), config.locale);
+
+    // Root was only needed for the GPIOs, fetch and parse as a normal user
+    if let Err(e) = privileges::drop_privileges(&config.privileges) {
+        error!("Failed to drop privileges: {}", e);
+        display.clear();
+        drop(display);
+        std::process::exit(1);
+    }
+
+    // Only after dropping root, the API is reachable from the network
+    start_http(&config, &state, Some(command_tx.clone()));
+    let mut mqtt = MqttOutput::start(&config, Some(command_tx));
+
+    // The panel is ours, tell systemd we're up
+    systemd.ready();
+
     info!("Starting live display...");
-    info!("  - Fetching data every 20 seconds");
-    info!("  - Cycling between top 3 departures every 10 seconds");
+    info!("  - Fetching data every {} seconds", config.fetch.interval);
+    info!("  - Rotating pages, starting with {:?}", pages.current().kind);
     info!("Press Ctrl+C to exit");
 
+    // Time of the last successful fetch, shown as data age in the status bar
+    let mut last_success: Option<DateTime<Utc>> = None;
+
     // Fetch initial data immediately
     info!("Fetching initial data...");
-    let mut departures: Vec<Departure> = match fetch_warschauer_str(&agent) {
+    let started = Instant::now();
+    let result = fetch_stops_with(&agent, &config.stops, &config.fetch, || systemd.watchdog());
+    report_fetch(&systemd, &state, &result, started);
+    mqtt.publish(&config, &result);
+    let mut departures: Vec<Departure> = match result {
         Ok(new_departures) => {
+            last_success = Some(Utc::now());
             if !new_departures.is_empty() {
-                let departures = new_departures.into_iter().take(3).collect::<Vec<_>>();
-                info!("Fetched {} departures", departures.len());
+                info!("Fetched {} departures", new_departures.len());
                 #[cfg(debug_assertions)]
                 {
-                    for dep in &departures {
+                    for dep in &new_departures {
                         debug!("  - {}", dep.format());
                     }
                 }
-                departures
+                new_departures
             } else {
                 warn!("No departures available");
                 Vec::new()
@@ -182,25 +592,64 @@ fn main() {
     };
 
     let mut last_fetch = std::time::Instant::now();
+    let mut fetch_now = false;
+    let mut watcher = ConfigWatcher::new(config_path());
     let mut last_display_change = std::time::Instant::now();
+    let mut last_clock = String::new();
     let mut needs_render = true;
+    // Set over the API, wins over the schedule until cleared
+    let mut brightness_override: Option<u8> = None;
+    let mut blanked = false;
 
     if !departures.is_empty() {
-        display.render_departures(&departures);
+        display.render_page(pages.current(), &departures, || signals.shutdown_requested());
     }
 
-    loop {
-        // Fetch new data every 20 seconds
-        if last_fetch.elapsed() >= Duration::from_secs(20) {
+    while !signals.shutdown_requested() {
+        systemd.watchdog();
+
+        // SIGHUP or a saved config file: new stops, schedule, pages and layout,
+        // refetch right away. The matrix itself keeps running, no GPIO re-init.
+        if (signals.take_reload() | watcher.changed()) && reload_config(&mut config, &systemd) {
+            if let Err(e) = display.reconfigure(config.display.clone()) {
+                error!("Display config not applied: {}", e);
+            }
+            display.set_format(config.format.clone(), config.locale);
+            pages.set_pages(config.display.pages.clone(), Instant::now());
+            state.lock().unwrap_or_else(|e| e.into_inner()).fetch_interval = config.fetch.interval;
+            fetch_now = true;
+            needs_render = true;
+        }
+
+        // Messages, brightness and pages from the API or MQTT
+        for command in commands.try_iter() {
+            info!(?command, "Command received");
+            match command {
+                Command::Message { text, duration, ttl, priority } => {
+                    let mut page = Page::new(PageKind::Text, duration);
+                    page.text = Some(text);
+                    pages.announce(page, ttl, priority, Instant::now());
+                }
+                Command::ClearMessages => pages.clear_messages(Instant::now()),
+                Command::Brightness(brightness) => brightness_override = brightness,
+                Command::ShowPage { kind, duration } => pages.interrupt(Page::new(kind, duration), Instant::now()),
+                Command::Blank(blank) => blanked = blank,
+            }
+            needs_render = true;
+        }
+
+        // Fetch new data every fetch.interval seconds
+        if fetch_now || last_fetch.elapsed() >= Duration::from_secs(config.fetch.interval) {
             info!("Refreshing data...");
-            match fetch_warschauer_str(&agent) {
-                Ok(mut new_departures) => {
+            let started = Instant::now();
+            let result = fetch_stops_with(&agent, &config.stops, &config.fetch, || systemd.watchdog());
+            report_fetch(&systemd, &state, &result, started);
+            mqtt.publish(&config, &result);
+            match result {
+                Ok(new_departures) => {
+                    last_success = Some(Utc::now());
                     if !new_departures.is_empty() {
-                        // Take only first 3 and immediately free the rest
-                        if new_departures.len() > 3 {
-                            new_departures.truncate(3);
-                            new_departures.shrink_to_fit();
-                        }
+                        // Keep the whole list, each page takes as many as it has room for
                         // Explicitly free old departures before replacing
                         drop(std::mem::replace(&mut departures, new_departures));
                         info!("Fetched {} departures", departures.len());
@@ -220,28 +669,85 @@ fn main() {
                 }
             }
             last_fetch = std::time::Instant::now();
+            fetch_now = false;
         }
 
-        // Change display every 10 seconds
-        if last_display_change.elapsed() >= Duration::from_secs(10) {
+        // Conditions and night mode go by the latest fetch, not the list kept on screen
+        let has_departures = state.lock().unwrap_or_else(|e| e.into_inner()).has_departures();
+
+        // Page rotation
+        let context = PageContext {
+            has_departures,
+            has_warnings: !collect_warnings(&departures).is_empty(),
+        };
+        let page_changed = pages.tick(Instant::now(), &context);
+        let page = pages.current();
+
+        // Departure page: next departure on every page switch and every `cycle` seconds
+        let cycle_due = last_display_change.elapsed() >= Duration::from_secs(page.cycle);
+        if page.kind == PageKind::Departure && (page_changed || cycle_due) {
             if departures.len() > 1 {
                 display.next_departure(departures.len());
                 let current_dep = &departures[display.current_index() % departures.len()];
                 debug!("Showing: {}", current_dep.format());
-                needs_render = true; // Changed departure, need to render
             }
             last_display_change = std::time::Instant::now();
         }
+        if page_changed {
+            needs_render = true; // Changed page, need to render
+        }
+
+        let now = schedule::now_local();
+
+        // Clock page: redraw when the minute changes
+        if page.kind == PageKind::Clock {
+            let clock = now.format("%H:%M").to_string();
+            if clock != last_clock {
+                last_clock = clock;
+                needs_render = true;
+            }
+        }
+
+        // Blanked or brightness from the API, else schedule / night mode
+        let level = match brightness_override {
+            _ if blanked => DisplayLevel::Blank,
+            Some(0) => DisplayLevel::Blank,
+            Some(brightness) => DisplayLevel::On(brightness),
+            None => config.display.schedule.level_at(now, config.display.brightness, has_departures),
+        };
+        if display.set_level(level) {
+            needs_render = true;
+        }
+
+        // Leave-now departures blink
+        if display.blink(&departures) {
+            needs_render = true;
+        }
+
+        // Clock and data age in the status bar
+        if display.update_status(&config.stops, now, last_success, config.fetch.interval) {
+            needs_render = true;
+        }
 
         // Render only when needed (not every loop iteration!)
-        if needs_render && !departures.is_empty() {
-            display.render_departures(&departures);
+        // Without departures only the status bar (or a blank panel) is drawn
+        if needs_render {
+            let started = Instant::now();
+            display.render_page(page, &departures, || signals.shutdown_requested());
+            state.lock().unwrap_or_else(|e| e.into_inner()).record_render(started.elapsed());
             needs_render = false;
         }
 
         // Sleep to avoid busy loop
-        thread::sleep(Duration::from_millis(500)); // Increased from 100ms to 500ms
+        signals.sleep(Duration::from_millis(500)); // Increased from 100ms to 500ms
     }
+
+    // Don't leave the last frame (or ghost pixels) on the panel
+    info!("Shutting down, clearing display");
+    systemd.stopping();
+    display.clear();
+    drop(display);
+    info!("Display released");
 }
 
 
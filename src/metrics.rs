@@ -0,0 +1,130 @@
+use crate::api::ErrorKind;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// Fetch latency buckets in seconds, the agent times out after 10s
+const FETCH_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+// Render buckets in seconds, transitions take a few hundred ms
+const RENDER_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+/// Prometheus histogram with fixed buckets
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    /// Per bucket, not cumulative, the last one is +Inf
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        let bucket = self.bounds.iter().position(|&b| value <= b).unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    fn write(&self, out: &mut String, name: &str, help: &str) {
+        header(out, name, help, "histogram");
+        let mut cumulative = 0;
+        for (i, count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            let le = self.bounds.get(i).map_or("+Inf".to_string(), |b| b.to_string());
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, le, cumulative);
+        }
+        let _ = writeln!(out, "{}_sum {}", name, self.sum);
+        let _ = writeln!(out, "{}_count {}", name, self.count);
+    }
+}
+
+/// Everything exported on /metrics, updated by the main loop
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    pub fetch_duration: Histogram,
+    pub fetch_success: u64,
+    /// Failed fetches by kind
+    pub fetch_errors: BTreeMap<ErrorKind, u64>,
+    /// Departures in the last successful fetch
+    pub departures: usize,
+    pub render_duration: Histogram,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            fetch_duration: Histogram::new(FETCH_BUCKETS),
+            fetch_success: 0,
+            fetch_errors: BTreeMap::new(),
+            departures: 0,
+            render_duration: Histogram::new(RENDER_BUCKETS),
+        }
+    }
+}
+
+impl Metrics {
+    /// Prometheus text format
+    pub fn render(&self, last_success: Option<DateTime<Utc>>, now: DateTime<Utc>) -> String {
+        let mut out = String::with_capacity(2048);
+
+        self.fetch_duration
+            .write(&mut out, "rusty_bvg_fetch_duration_seconds", "Time to fetch all stops");
+
+        header(&mut out, "rusty_bvg_fetch_success_total", "Successful fetches", "counter");
+        let _ = writeln!(out, "rusty_bvg_fetch_success_total {}", self.fetch_success);
+
+        header(&mut out, "rusty_bvg_fetch_errors_total", "Failed fetches by error kind", "counter");
+        for kind in ErrorKind::ALL {
+            let count = self.fetch_errors.get(&kind).copied().unwrap_or(0);
+            let _ = writeln!(out, "rusty_bvg_fetch_errors_total{{kind=\"{}\"}} {}", kind.label(), count);
+        }
+
+        header(&mut out, "rusty_bvg_departures", "Departures in the last successful fetch", "gauge");
+        let _ = writeln!(out, "rusty_bvg_departures {}", self.departures);
+
+        // No sample until the first successful fetch
+        header(&mut out, "rusty_bvg_data_age_seconds", "Seconds since the last successful fetch", "gauge");
+        if let Some(last_success) = last_success {
+            let age = now.signed_duration_since(last_success).num_milliseconds() as f64 / 1000.0;
+            let _ = writeln!(out, "rusty_bvg_data_age_seconds {}", age);
+        }
+
+        self.render_duration
+            .write(&mut out, "rusty_bvg_render_duration_seconds", "Time to draw a page, transitions included");
+
+        header(&mut out, "process_resident_memory_bytes", "Resident memory size in bytes", "gauge");
+        if let Some(rss) = resident_memory_bytes() {
+            let _ = writeln!(out, "process_resident_memory_bytes {}", rss);
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Resident set size from /proc, None where there is no procfs
+pub fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    // "VmRSS:     5120 kB"
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
@@ -0,0 +1,209 @@
+use crate::config::StopConfig;
+use crate::departure::Departure;
+use crate::output::Entry;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+
+/// MQTT output, `[mqtt]` in the config (needs the `mqtt` feature)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MqttConfig {
+    /// Broker host name
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Also the device ID in Home Assistant, must be unique per board
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
+    /// Topics start with this, e.g. "rusty-bvg/900120003/departures"
+    #[serde(default = "default_topic_prefix")]
+    pub topic_prefix: String,
+    /// Publish Home Assistant discovery messages
+    #[serde(default = "default_discovery")]
+    pub discovery: bool,
+    #[serde(default = "default_discovery_prefix")]
+    pub discovery_prefix: String,
+    /// Accept commands (messages, brightness, ...) on "<topic_prefix>/command"
+    #[serde(default = "default_commands")]
+    pub commands: bool,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_client_id() -> String {
+    "rusty-bvg".to_string()
+}
+
+fn default_topic_prefix() -> String {
+    "rusty-bvg".to_string()
+}
+
+fn default_discovery() -> bool {
+    true
+}
+
+fn default_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+fn default_commands() -> bool {
+    true
+}
+
+/// One MQTT publish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub topic: String,
+    pub payload: String,
+    pub retain: bool,
+    /// (stop, line) of a discovery message, see `BoardPublisher::published`
+    pub announces: Option<(String, String)>,
+}
+
+impl Message {
+    fn retained(topic: String, payload: String) -> Self {
+        Self { topic, payload, retain: true, announces: None }
+    }
+}
+
+/// Turns fetched departures into MQTT messages
+/// Remembers which line sensors exist to announce new ones and clear vanished ones
+pub struct BoardPublisher {
+    config: MqttConfig,
+    /// (stop, line) pairs whose discovery message was queued
+    announced: BTreeSet<(String, String)>,
+    /// (stop, line) pairs with a departure in the last publish
+    active: BTreeSet<(String, String)>,
+}
+
+impl BoardPublisher {
+    pub fn new(config: &MqttConfig) -> Self {
+        Self {
+            config: config.clone(),
+            announced: BTreeSet::new(),
+            active: BTreeSet::new(),
+        }
+    }
+
+    /// "online" while connected, "offline" as last will
+    pub fn availability_topic(&self) -> String {
+        format!("{}/status", self.config.topic_prefix)
+    }
+
+    /// JSON commands for the board, see `control::parse_command`
+    pub fn command_topic(&self) -> String {
+        format!("{}/command", self.config.topic_prefix)
+    }
+
+    /// Messages for one fetch: the board per stop and the next departure per line
+    pub fn messages(&mut self, stops: &[StopConfig], departures: &[Departure], now: DateTime<Utc>) -> Vec<Message> {
+        let mut messages = Vec::new();
+        let mut active = BTreeSet::new();
+
+        for stop in stops {
+            let board: Vec<&Departure> = departures
+                .iter()
+                .filter(|d| d.stop.as_deref() == Some(stop.id.as_str()))
+                .collect();
+
+            messages.push(Message::retained(
+                format!("{}/{}/departures", self.config.topic_prefix, topic_part(&stop.id)),
+                json!({
+                    "stop": stop.id,
+                    "name": stop.name,
+                    "updated": now.to_rfc3339(),
+                    "departures": board.iter().map(|d| Entry::from(*d)).collect::<Vec<_>>(),
+                })
+                .to_string(),
+            ));
+
+            // Departures are sorted, the first one per line is the next one
+            for departure in &board {
+                let key = (stop.id.clone(), departure.line.clone());
+                if !active.insert(key.clone()) {
+                    continue;
+                }
+                if self.config.discovery && !self.announced.contains(&key) {
+                    messages.push(Message { announces: Some(key), ..self.discovery(stop, &departure.line) });
+                }
+                messages.push(Message::retained(
+                    self.line_topic(&stop.id, &departure.line),
+                    json!({
+                        "minutes": departure.minutes,
+                        "destination": departure.destination,
+                        "urgency": departure.urgency,
+                    })
+                    .to_string(),
+                ));
+            }
+        }
+
+        // Lines without a departure any more: unknown instead of a stale countdown
+        for (stop, line) in self.active.difference(&active) {
+            messages.push(Message::retained(self.line_topic(stop, line), json!({ "minutes": null }).to_string()));
+        }
+        self.active = active;
+
+        messages
+    }
+
+    /// Call for every message the client accepted
+    /// Discovery is repeated with every publish until its message got through
+    pub fn published(&mut self, message: &Message) {
+        if let Some(key) = &message.announces {
+            self.announced.insert(key.clone());
+        }
+    }
+
+    fn line_topic(&self, stop: &str, line: &str) -> String {
+        format!("{}/{}/lines/{}", self.config.topic_prefix, topic_part(stop), topic_part(line))
+    }
+
+    /// Home Assistant MQTT discovery for a "next departure in N min" sensor
+    fn discovery(&self, stop: &StopConfig, line: &str) -> Message {
+        let object_id = format!("{}_{}", topic_part(&stop.id), topic_part(line));
+        let node_id = topic_part(&self.config.client_id);
+        let stop_name = stop.name.as_deref().unwrap_or(&stop.id);
+        let icon = match line.chars().next() {
+            Some('U') => "mdi:subway-variant",
+            Some('S') => "mdi:train",
+            _ => "mdi:tram",
+        };
+
+        let payload = json!({
+            "name": format!("{} {}", line, stop_name),
+            "unique_id": format!("{}_{}", node_id, object_id),
+            "state_topic": self.line_topic(&stop.id, line),
+            "value_template": "{{ value_json.minutes }}",
+            "json_attributes_topic": self.line_topic(&stop.id, line),
+            "unit_of_measurement": "min",
+            "icon": icon,
+            "availability_topic": self.availability_topic(),
+            "device": {
+                "identifiers": [node_id],
+                "name": format!("rusty-bvg {}", self.config.client_id),
+                "manufacturer": "rusty-bvg",
+            },
+        });
+
+        Message::retained(
+            format!("{}/sensor/{}/{}/config", self.config.discovery_prefix, node_id, object_id),
+            payload.to_string(),
+        )
+    }
+}
+
+/// Topic levels and discovery IDs: letters, digits, '-' and '_' only
+fn topic_part(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,106 @@
+use crate::config::StopConfig;
+use crate::control::{self, Command};
+use crate::departure::Departure;
+use crate::mqtt::{BoardPublisher, MqttConfig};
+use chrono::Utc;
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+// Messages queued while the broker is away, older boards are worthless anyway
+const QUEUE_CAPACITY: usize = 64;
+// Wait between reconnect attempts
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Connection to the broker, topics and payloads come from mqtt.rs
+pub struct MqttClient {
+    client: Client,
+    publisher: BoardPublisher,
+}
+
+impl MqttClient {
+    /// Start the connection thread, connecting and reconnecting happens there
+    /// Commands from the command topic go to `commands`, None (no display) doesn't subscribe
+    pub fn connect(config: &MqttConfig, commands: Option<Sender<Command>>) -> Result<Self, String> {
+        let publisher = BoardPublisher::new(config);
+        let availability = publisher.availability_topic();
+        let command_topic = (config.commands && commands.is_some()).then(|| publisher.command_topic());
+
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        options.set_last_will(LastWill::new(&availability, "offline", QoS::AtLeastOnce, true));
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            options.set_credentials(username, password);
+        }
+
+        let (client, mut connection) = Client::new(options, QUEUE_CAPACITY);
+        let online_client = client.clone();
+        let broker = format!("{}:{}", config.host, config.port);
+
+        thread::Builder::new()
+            .name("mqtt".to_string())
+            .spawn(move || {
+                for event in connection.iter() {
+                    match event {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            info!(broker = %broker, "MQTT connected");
+                            if let Err(e) = online_client.try_publish(&availability, QoS::AtLeastOnce, true, "online") {
+                                warn!("MQTT availability not published: {}", e);
+                            }
+                            // Subscriptions don't survive a reconnect with a clean session
+                            if let Some(topic) = &command_topic
+                                && let Err(e) = online_client.try_subscribe(topic, QoS::AtLeastOnce)
+                            {
+                                warn!("MQTT command topic not subscribed: {}", e);
+                            }
+                        }
+                        Ok(Event::Incoming(Packet::Publish(publish))) => {
+                            if command_topic.as_deref() != Some(publish.topic.as_str()) {
+                                continue;
+                            }
+                            // A retained command would come back on every restart
+                            if publish.retain {
+                                warn!(topic = %publish.topic, "Ignoring retained MQTT command");
+                                continue;
+                            }
+                            let command = std::str::from_utf8(&publish.payload)
+                                .map_err(|e| format!("invalid command: {}", e))
+                                .and_then(control::parse_command);
+                            match command {
+                                Ok(command) => {
+                                    if commands.as_ref().is_some_and(|c| c.send(command).is_err()) {
+                                        break;
+                                    }
+                                }
+                                Err(e) => warn!("MQTT command rejected: {}", e),
+                            }
+                        }
+                        Ok(event) => debug!(?event, "MQTT event"),
+                        Err(e) => {
+                            warn!(broker = %broker, "MQTT connection error: {}", e);
+                            thread::sleep(RECONNECT_DELAY);
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start MQTT thread: {}", e))?;
+
+        Ok(Self { client, publisher })
+    }
+
+    /// Publish the board of every stop and the per-line sensors
+    /// Never blocks the main loop: a full queue drops the update
+    pub fn publish_board(&mut self, stops: &[StopConfig], departures: &[Departure]) {
+        for message in self.publisher.messages(stops, departures, Utc::now()) {
+            match self
+                .client
+                .try_publish(&message.topic, QoS::AtLeastOnce, message.retain, message.payload.as_str())
+            {
+                Ok(()) => self.publisher.published(&message),
+                Err(e) => warn!("MQTT publish dropped: {}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::departure::Departure;
+use crate::locale::Locale;
+use crate::ring::RingDirection;
+use crate::template::Template;
+use crate::urgency::Urgency;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// What the console build writes to stdout per fetch, `--output <format>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Nothing on stdout, only log lines on stderr
+    #[default]
+    Log,
+    /// One JSON document per board and line
+    Json,
+    /// One JSON line per departure
+    Ndjson,
+    /// Aligned columns for humans
+    Table,
+    /// "U1 Uhlandstr. 3 min" per departure, `[format] departure`
+    Plain,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "log" => Ok(OutputFormat::Log),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "table" => Ok(OutputFormat::Table),
+            "plain" => Ok(OutputFormat::Plain),
+            _ => Err(format!("Unknown output format '{}', expected json, ndjson, table or plain", s)),
+        }
+    }
+}
+
+/// Output schema of a departure, kept stable for scripts, MQTT and the HTTP API
+/// Independent of `Departure` so internal changes don't break consumers
+#[derive(Debug, Serialize)]
+pub struct Entry<'a> {
+    stop: Option<&'a str>,
+    line: &'a str,
+    destination: &'a str,
+    minutes: u32,
+    urgency: Option<Urgency>,
+    warnings: &'a [String],
+    delay: Option<i32>,
+    platform: Option<&'a str>,
+    planned: Option<String>,
+    cancelled: bool,
+    ring: Option<RingDirection>,
+}
+
+impl<'a> From<&'a Departure> for Entry<'a> {
+    fn from(departure: &'a Departure) -> Self {
+        Self {
+            stop: departure.stop.as_deref(),
+            line: &departure.line,
+            destination: &departure.destination,
+            minutes: departure.minutes,
+            urgency: departure.urgency,
+            warnings: &departure.warnings,
+            delay: departure.delay,
+            platform: departure.platform.as_deref(),
+            planned: departure.planned.map(|t| t.to_rfc3339()),
+            cancelled: departure.cancelled,
+            ring: departure.ring,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Board<'a> {
+    updated: String,
+    departures: Vec<Entry<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Line<'a> {
+    updated: &'a str,
+    #[serde(flatten)]
+    entry: Entry<'a>,
+}
+
+/// One fetched board in the given format, None for `Log`
+/// Every line ends with a newline, an empty board gives an empty string
+/// except for JSON, where an empty list is still a board
+/// `template` is the plain text of a departure, `locale` the language of plain and table
+pub fn render(
+    format: OutputFormat,
+    departures: &[Departure],
+    updated: DateTime<Utc>,
+    template: &Template,
+    locale: Locale,
+) -> Option<String> {
+    let updated = updated.to_rfc3339();
+    let out = match format {
+        OutputFormat::Log => return None,
+        OutputFormat::Json => {
+            let board = Board {
+                updated,
+                departures: departures.iter().map(Entry::from).collect(),
+            };
+            serde_json::to_string(&board).ok()? + "\n"
+        }
+        OutputFormat::Ndjson => departures
+            .iter()
+            .filter_map(|d| serde_json::to_string(&Line { updated: &updated, entry: d.into() }).ok())
+            .map(|line| line + "\n")
+            .collect(),
+        OutputFormat::Table => table(departures, locale),
+        OutputFormat::Plain => departures
+            .iter()
+            .map(|d| d.format_with(template, &locale.words()) + "\n")
+            .collect(),
+    };
+    Some(out)
+}
+
+/// Columns sized to the widest entry, minutes right-aligned, a blank line after the board
+fn table(departures: &[Departure], locale: Locale) -> String {
+    if departures.is_empty() {
+        return String::new();
+    }
+
+    let strings = locale.strings();
+    let minutes = |d: &Departure| if d.cancelled { strings.cancelled.to_string() } else { d.minutes.to_string() };
+    let width = |header: &str, column: &dyn Fn(&Departure) -> usize| {
+        departures.iter().map(column).max().unwrap_or(0).max(header.chars().count())
+    };
+    let (line, destination, min) = (strings.line.to_uppercase(), strings.destination.to_uppercase(), strings.min.to_uppercase());
+    let line_width = width(&line, &|d| d.line.chars().count());
+    let destination_width = width(&destination, &|d| d.destination_label().chars().count());
+    let minutes_width = width(&min, &|d| minutes(d).chars().count());
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{:<line_width$}  {:<destination_width$}  {:>minutes_width$}", line, destination, min);
+    for departure in departures {
+        let _ = writeln!(
+            out,
+            "{:<line_width$}  {:<destination_width$}  {:>minutes_width$}",
+            departure.line,
+            departure.destination_label(),
+            minutes(departure)
+        );
+    }
+    out.push('\n');
+    out
+}
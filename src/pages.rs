@@ -0,0 +1,293 @@
+use crate::transition::Transition;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// What a page shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageKind {
+    /// One row per departure: badge, destination, minutes
+    Board,
+    /// One departure at a time with the destination wrapped over two lines
+    Departure,
+    /// Warnings from the API (construction work, disruptions)
+    Disruptions,
+    /// Time and date
+    Clock,
+    /// Static text from the config
+    Text,
+}
+
+/// When a page is part of the rotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    #[default]
+    Always,
+    HasDepartures,
+    HasWarnings,
+}
+
+/// One page in the rotation, `[[display.pages]]` in the config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Page {
+    #[serde(rename = "type")]
+    pub kind: PageKind,
+    /// Seconds the page stays on screen
+    #[serde(default = "default_duration")]
+    pub duration: u64,
+    #[serde(default)]
+    pub when: Condition,
+    /// Text for `type = "text"`
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Departure page only: seconds until the next departure is shown
+    #[serde(default = "default_cycle")]
+    pub cycle: u64,
+    /// Animation when this page appears
+    #[serde(default)]
+    pub transition: Transition,
+}
+
+fn default_duration() -> u64 {
+    10
+}
+
+fn default_cycle() -> u64 {
+    10
+}
+
+impl Page {
+    pub fn new(kind: PageKind, duration: u64) -> Self {
+        Self {
+            kind,
+            duration,
+            when: Condition::Always,
+            text: None,
+            cycle: default_cycle(),
+            transition: Transition::None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.duration == 0 || self.cycle == 0 {
+            return Err("display.pages: duration and cycle must be > 0".to_string());
+        }
+        if self.kind == PageKind::Text && self.text.as_deref().is_none_or(str::is_empty) {
+            return Err("display.pages: type = \"text\" needs a text".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The rotation we had before pages existed: cycle through departures every 10 seconds
+pub fn default_pages() -> Vec<Page> {
+    vec![Page::new(PageKind::Departure, 10)]
+}
+
+/// Current board state the page conditions are checked against
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PageContext {
+    pub has_departures: bool,
+    pub has_warnings: bool,
+}
+
+impl Condition {
+    fn matches(self, context: &PageContext) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::HasDepartures => context.has_departures,
+            Condition::HasWarnings => context.has_warnings,
+        }
+    }
+}
+
+/// How an announcement enters the rotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Waits for its turn in the rotation
+    Low,
+    /// Shown right away, then joins the rotation
+    #[default]
+    Normal,
+    /// Stays on screen until its TTL runs out, nothing else interrupts it
+    High,
+}
+
+// Announcements in the rotation at the same time, the oldest one goes first
+const MAX_MESSAGES: usize = 10;
+
+/// Text page pushed from outside, part of the rotation until `expires`
+struct Message {
+    page: Page,
+    expires: Instant,
+}
+
+/// Rotates through the configured pages, skipping those whose condition isn't met
+/// Announcements are appended to the rotation until their TTL runs out
+pub struct PageScheduler {
+    pages: Vec<Page>,
+    messages: Vec<Message>,
+    /// Index into pages, then messages
+    current: usize,
+    shown_since: Instant,
+    /// Page pushed from outside (e.g. a message over HTTP), shown once for its duration
+    interrupt: Option<(Page, Priority)>,
+}
+
+impl PageScheduler {
+    pub fn new(pages: Vec<Page>, now: Instant) -> Self {
+        let pages = if pages.is_empty() { default_pages() } else { pages };
+        Self {
+            pages,
+            messages: Vec::new(),
+            current: 0,
+            shown_since: now,
+            interrupt: None,
+        }
+    }
+
+    /// New pages from a reloaded config, announcements stay
+    pub fn set_pages(&mut self, pages: Vec<Page>, now: Instant) {
+        self.pages = if pages.is_empty() { default_pages() } else { pages };
+        self.current = 0;
+        self.shown_since = now;
+    }
+
+    pub fn current(&self) -> &Page {
+        match &self.interrupt {
+            Some((page, _)) => page,
+            None => self.page_at(self.current),
+        }
+    }
+
+    fn page_at(&self, index: usize) -> &Page {
+        match index.checked_sub(self.pages.len()) {
+            Some(message) => &self.messages[message].page,
+            None => &self.pages[index],
+        }
+    }
+
+    fn expired(&self, index: usize, now: Instant) -> bool {
+        index
+            .checked_sub(self.pages.len())
+            .is_some_and(|message| self.messages[message].expires <= now)
+    }
+
+    /// Show a page right away, the rotation continues where it left off afterwards
+    /// A newer interrupt replaces an older one, except for a high priority announcement
+    pub fn interrupt(&mut self, page: Page, now: Instant) {
+        self.interrupt_with(page, Priority::Normal, now);
+    }
+
+    fn interrupt_with(&mut self, page: Page, priority: Priority, now: Instant) {
+        if priority != Priority::High
+            && let Some((shown, Priority::High)) = &self.interrupt
+            && now.duration_since(self.shown_since) < Duration::from_secs(shown.duration)
+        {
+            debug!(kind = ?page.kind, "High priority announcement on screen, not interrupting");
+            return;
+        }
+        debug!(kind = ?page.kind, duration = page.duration, ?priority, "Interrupting rotation");
+        self.interrupt = Some((page, priority));
+        self.shown_since = now;
+    }
+
+    /// Add an announcement (a text page) to the rotation for `ttl` seconds
+    pub fn announce(&mut self, page: Page, ttl: u64, priority: Priority, now: Instant) {
+        if priority == Priority::High {
+            // On screen for the whole TTL, no need to rotate it as well
+            let mut page = page;
+            page.duration = ttl;
+            self.interrupt_with(page, priority, now);
+            return;
+        }
+
+        if priority == Priority::Normal {
+            self.interrupt_with(page.clone(), priority, now);
+        }
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.remove(0);
+            if self.current > self.pages.len() {
+                self.current -= 1;
+            }
+        }
+        self.messages.push(Message {
+            page,
+            expires: now + Duration::from_secs(ttl),
+        });
+    }
+
+    /// Drop all announcements, also one currently on screen
+    pub fn clear_messages(&mut self, now: Instant) {
+        self.messages.clear();
+        if self.current >= self.pages.len() {
+            self.current = 0;
+            self.shown_since = now;
+        }
+        if self.interrupt.as_ref().is_some_and(|(page, _)| page.kind == PageKind::Text) {
+            self.interrupt = None;
+            self.shown_since = now;
+        }
+    }
+
+    /// Advance when the current page has been shown long enough,
+    /// or right away if its condition no longer holds or its TTL ran out.
+    /// Returns true when another page was activated and the screen needs a redraw
+    pub fn tick(&mut self, now: Instant, context: &PageContext) -> bool {
+        // Interrupts ignore conditions, they were asked for explicitly
+        if let Some((page, _)) = &self.interrupt {
+            if now.duration_since(self.shown_since) < Duration::from_secs(page.duration) {
+                return false;
+            }
+            self.interrupt = None;
+            self.shown_since = now;
+            return true;
+        }
+
+        let shown_long_enough = now.duration_since(self.shown_since) >= Duration::from_secs(self.current().duration);
+        if !shown_long_enough && !self.expired(self.current, now) && self.current().when.matches(context) {
+            return false;
+        }
+
+        let old = self.current;
+        let len = self.pages.len() + self.messages.len();
+        // Next page whose condition holds, the current one included as last resort
+        let next = (1..=len)
+            .map(|offset| (self.current + offset) % len)
+            .find(|&i| !self.expired(i, now) && self.page_at(i).when.matches(context));
+
+        // Nothing matches: stay on the current page, it's better than a black screen
+        // An expired announcement can't stay, fall back to the first page
+        self.current = match next {
+            Some(next) => next,
+            None if self.expired(self.current, now) => 0,
+            None => self.current,
+        };
+
+        // Announcements past their TTL leave the rotation
+        let first = self.pages.len();
+        let before = self.messages.len();
+        let mut index = first;
+        let mut removed_before = 0;
+        self.messages.retain(|message| {
+            let keep = message.expires > now;
+            if !keep && index < self.current {
+                removed_before += 1;
+            }
+            index += 1;
+            keep
+        });
+        self.current -= removed_before;
+
+        self.shown_since = now;
+        if self.current == old && self.messages.len() == before {
+            return false;
+        }
+        debug!(old_page = old, new_page = self.current, kind = ?self.current().kind, "Switching page");
+        true
+    }
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+use std::ffi::CString;
+use tracing::{info, warn};
+
+/// User and group to switch to once the matrix is set up, `[privileges]` in the config
+/// GPIO access needs root, fetching and parsing JSON from the internet doesn't
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrivilegesConfig {
+    /// "daemon" by default, like rpi-rgb-led-matrix itself; "root" keeps running as root
+    pub user: Option<String>,
+    /// Defaults to the user's primary group
+    pub group: Option<String>,
+}
+
+impl Default for PrivilegesConfig {
+    fn default() -> Self {
+        Self {
+            user: Some("daemon".to_string()),
+            group: None,
+        }
+    }
+}
+
+/// uid and primary gid of a user
+pub fn lookup_user(name: &str) -> Result<(libc::uid_t, libc::gid_t), String> {
+    let c_name = CString::new(name).map_err(|_| format!("Invalid user name: {}", name))?;
+    // Called once at startup, before any other thread looks up users
+    let passwd = unsafe { libc::getpwnam(c_name.as_ptr()) };
+    if passwd.is_null() {
+        return Err(format!("Unknown user: {}", name));
+    }
+    let passwd = unsafe { &*passwd };
+    Ok((passwd.pw_uid, passwd.pw_gid))
+}
+
+pub fn lookup_group(name: &str) -> Result<libc::gid_t, String> {
+    let c_name = CString::new(name).map_err(|_| format!("Invalid group name: {}", name))?;
+    let group = unsafe { libc::getgrnam(c_name.as_ptr()) };
+    if group.is_null() {
+        return Err(format!("Unknown group: {}", name));
+    }
+    Ok(unsafe { (*group).gr_gid })
+}
+
+/// Switch to the configured user and group for good
+/// Call right after the matrix is initialised, its refresh thread keeps its GPIO access
+pub fn drop_privileges(config: &PrivilegesConfig) -> Result<(), String> {
+    let Some(user) = &config.user else {
+        return Ok(());
+    };
+    if user == "root" {
+        warn!("Configured to keep running as root");
+        return Ok(());
+    }
+
+    if unsafe { libc::geteuid() } != 0 {
+        warn!(user = %user, "Not running as root, no privileges to drop");
+        return Ok(());
+    }
+
+    let (uid, primary_gid) = lookup_user(user)?;
+    let gid = match &config.group {
+        Some(group) => lookup_group(group)?,
+        None => primary_gid,
+    };
+
+    // Group first: after setuid we're no longer allowed to change it
+    let c_user = CString::new(user.as_str()).map_err(|_| format!("Invalid user name: {}", user))?;
+    if unsafe { libc::initgroups(c_user.as_ptr(), gid) } != 0 {
+        return Err(format!("initgroups failed: {}", std::io::Error::last_os_error()));
+    }
+    if unsafe { libc::setgid(gid) } != 0 {
+        return Err(format!("setgid({}) failed: {}", gid, std::io::Error::last_os_error()));
+    }
+    if unsafe { libc::setuid(uid) } != 0 {
+        return Err(format!("setuid({}) failed: {}", uid, std::io::Error::last_os_error()));
+    }
+
+    // Make sure there is no way back
+    if unsafe { libc::setuid(0) } == 0 {
+        return Err("Still able to regain root after dropping privileges".to_string());
+    }
+
+    info!(user = %user, uid, gid, "Dropped root privileges");
+    Ok(())
+}
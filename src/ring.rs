@@ -0,0 +1,102 @@
+use crate::destination;
+use serde::{Deserialize, Serialize};
+
+/// Ringbahn stations in clockwise (S41) order, starting at Südkreuz
+const STATIONS: [&str; 27] = [
+    "Südkreuz",
+    "Schöneberg",
+    "Innsbrucker Platz",
+    "Bundesplatz",
+    "Heidelberger Platz",
+    "Hohenzollerndamm",
+    "Halensee",
+    "Westkreuz",
+    "Messe Nord/ICC",
+    "Westend",
+    "Jungfernheide",
+    "Beusselstr.",
+    "Westhafen",
+    "Wedding",
+    "Gesundbrunnen",
+    "Schönhauser Allee",
+    "Prenzlauer Allee",
+    "Greifswalder Str.",
+    "Landsberger Allee",
+    "Storkower Str.",
+    "Frankfurter Allee",
+    "Ostkreuz",
+    "Treptower Park",
+    "Sonnenallee",
+    "Neukölln",
+    "Hermannstr.",
+    "Tempelhof",
+];
+
+/// Where the Ring meets the lines across the city, shown as the destination
+const INTERCHANGES: [&str; 4] = ["Südkreuz", "Westkreuz", "Gesundbrunnen", "Ostkreuz"];
+
+/// 5x5 arrow drawn in front of the destination on the LED board, `#` is lit
+const CLOCKWISE_GLYPH: [&str; 5] = [".##..", "#..#.", "#.###", "#..#.", ".#..."];
+const COUNTER_CLOCKWISE_GLYPH: [&str; 5] = ["..##.", ".#..#", "###.#", ".#..#", "...#."];
+
+/// Direction of a Ringbahn train, there is no end station to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RingDirection {
+    /// S41
+    Clockwise,
+    /// S42
+    CounterClockwise,
+}
+
+impl RingDirection {
+    pub fn for_line(line: &str) -> Option<Self> {
+        match line {
+            "S41" => Some(RingDirection::Clockwise),
+            "S42" => Some(RingDirection::CounterClockwise),
+            _ => None,
+        }
+    }
+
+    pub fn reversed(self) -> Self {
+        match self {
+            RingDirection::Clockwise => RingDirection::CounterClockwise,
+            RingDirection::CounterClockwise => RingDirection::Clockwise,
+        }
+    }
+
+    /// For text output, the LED fonts draw `glyph` instead
+    pub fn arrow(self) -> char {
+        match self {
+            RingDirection::Clockwise => '⟳',
+            RingDirection::CounterClockwise => '⟲',
+        }
+    }
+
+    pub fn glyph(self) -> &'static [&'static str; 5] {
+        match self {
+            RingDirection::Clockwise => &CLOCKWISE_GLYPH,
+            RingDirection::CounterClockwise => &COUNTER_CLOCKWISE_GLYPH,
+        }
+    }
+
+    /// Next interchange after `stop` (an API stop name) in this direction
+    /// None if the stop isn't on the Ring
+    pub fn via(self, stop: &str) -> Option<&'static str> {
+        let stop = normalize(&destination::clean(stop, &[], true));
+        let position = STATIONS.iter().position(|s| normalize(s) == stop)?;
+
+        (1..STATIONS.len())
+            .map(|step| match self {
+                RingDirection::Clockwise => (position + step) % STATIONS.len(),
+                RingDirection::CounterClockwise => (position + STATIONS.len() - step) % STATIONS.len(),
+            })
+            .map(|i| STATIONS[i])
+            .find(|station| INTERCHANGES.contains(station))
+    }
+}
+
+/// VBB writes both "Str." and "Straße"
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace("straße", "str.")
+}
@@ -0,0 +1,220 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer};
+
+/// All schedules run on Berlin wall-clock time, whatever the Pi's timezone is
+pub const TIMEZONE: Tz = chrono_tz::Europe::Berlin;
+
+// Alexanderplatz, used when [display.schedule.sun] has no coordinates
+const BERLIN_LATITUDE: f64 = 52.5219;
+const BERLIN_LONGITUDE: f64 = 13.4132;
+
+pub fn now_local() -> DateTime<Tz> {
+    Utc::now().with_timezone(&TIMEZONE)
+}
+
+/// Time of day written as "HH:MM" in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay(pub NaiveTime);
+
+impl TimeOfDay {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .map(TimeOfDay)
+            .map_err(|_| format!("invalid time '{}', expected HH:MM", value))
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        TimeOfDay::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// What the panel should do right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayLevel {
+    /// Draw normally at this brightness (percent)
+    On(u8),
+    /// Panel completely dark
+    Blank,
+}
+
+/// Brightness over the day, `[display.schedule]` in the config
+/// Night mode wins over fixed steps, fixed steps win over sunrise/sunset
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Schedule {
+    /// Brightness from a time of day on, until the next step
+    pub steps: Vec<BrightnessStep>,
+    /// Dim between sunset and sunrise
+    pub sun: Option<SunSchedule>,
+    /// Dim or blank the panel during the night
+    pub night: Option<NightMode>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BrightnessStep {
+    pub from: TimeOfDay,
+    pub brightness: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SunSchedule {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Brightness between sunset and sunrise, daytime uses display.brightness
+    pub night_brightness: u8,
+}
+
+impl Default for SunSchedule {
+    fn default() -> Self {
+        Self {
+            latitude: BERLIN_LATITUDE,
+            longitude: BERLIN_LONGITUDE,
+            night_brightness: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NightAction {
+    Dim,
+    Blank,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NightMode {
+    pub start: TimeOfDay,
+    pub end: TimeOfDay,
+    pub action: NightAction,
+    /// Brightness for action = "dim"
+    #[serde(default = "default_night_brightness")]
+    pub brightness: u8,
+    /// From this time on the panel wakes up early as soon as there is a departure
+    pub wake_after: Option<TimeOfDay>,
+}
+
+fn default_night_brightness() -> u8 {
+    10
+}
+
+impl NightMode {
+    fn contains(&self, time: NaiveTime) -> bool {
+        in_window(time, self.start.0, self.end.0)
+    }
+}
+
+// Time window that may wrap around midnight (23:00-06:00)
+fn in_window(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        time >= start && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+impl Schedule {
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty() && self.sun.is_none() && self.night.is_none()
+    }
+
+    /// Level at the given local time
+    /// `has_departures` (the latest fetch found any) lets night mode wake up for the first departure after `wake_after`
+    pub fn level_at(&self, now: DateTime<Tz>, default_brightness: u8, has_departures: bool) -> DisplayLevel {
+        let time = now.time();
+
+        if let Some(night) = &self.night {
+            let woken = has_departures
+                && night.wake_after.is_some_and(|wake| in_window(time, wake.0, night.end.0));
+            if night.contains(time) && !woken {
+                return match night.action {
+                    NightAction::Dim => DisplayLevel::On(night.brightness),
+                    NightAction::Blank => DisplayLevel::Blank,
+                };
+            }
+        }
+
+        if let Some(brightness) = self.step_brightness(time) {
+            return DisplayLevel::On(brightness);
+        }
+
+        if let Some(sun) = &self.sun {
+            let date = now.date_naive();
+            if let Some((sunrise, sunset)) = sun_times(date, sun.latitude, sun.longitude) {
+                let now_utc = now.with_timezone(&Utc);
+                if now_utc < sunrise || now_utc >= sunset {
+                    return DisplayLevel::On(sun.night_brightness);
+                }
+            }
+        }
+
+        DisplayLevel::On(default_brightness)
+    }
+
+    // Latest step before `time`, wrapping around to yesterday's last step
+    fn step_brightness(&self, time: NaiveTime) -> Option<u8> {
+        let today = self.steps.iter().filter(|s| s.from.0 <= time).max_by_key(|s| s.from);
+        let yesterday = || self.steps.iter().max_by_key(|s| s.from);
+        today.or_else(yesterday).map(|s| s.brightness)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let brightness_ok = |b: u8| (1..=100).contains(&b);
+        if self.steps.iter().any(|s| !brightness_ok(s.brightness)) {
+            return Err("display.schedule.steps brightness must be 1-100".to_string());
+        }
+        if self.sun.as_ref().is_some_and(|s| !brightness_ok(s.night_brightness)) {
+            return Err("display.schedule.sun.night_brightness must be 1-100".to_string());
+        }
+        if let Some(night) = &self.night {
+            if night.action == NightAction::Dim && !brightness_ok(night.brightness) {
+                return Err("display.schedule.night.brightness must be 1-100".to_string());
+            }
+            if night.wake_after.is_some_and(|wake| !night.contains(wake.0)) {
+                return Err("display.schedule.night.wake_after must be inside the night window".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sunrise and sunset (UTC) for a date and position
+/// Uses the sunrise equation, accurate to a minute or two, which is plenty for dimming.
+/// None during polar day/night.
+pub fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    // Days since J2000 (2000-01-01 12:00 UTC)
+    let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1)?;
+    let n = (date.num_days_from_ce() - j2000.num_days_from_ce()) as f64;
+
+    let mean_solar_noon = n - longitude / 360.0;
+    let anomaly = (357.5291 + 0.98560028 * mean_solar_noon).rem_euclid(360.0).to_radians();
+    let center = 1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude = (anomaly.to_degrees() + center + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+    let transit = 2451545.0 + mean_solar_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+    let declination = (ecliptic_longitude.sin() * 23.4397f64.to_radians().sin()).asin();
+    let latitude = latitude.to_radians();
+    // -0.833° accounts for refraction and the size of the sun's disc
+    let cos_hour_angle = ((-0.833f64).to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    let julian_to_utc = |julian: f64| {
+        let seconds = ((julian - 2440587.5) * 86400.0).round() as i64;
+        Utc.timestamp_opt(seconds, 0).single()
+    };
+
+    Some((
+        julian_to_utc(transit - hour_angle / 360.0)?,
+        julian_to_utc(transit + hour_angle / 360.0)?,
+    ))
+}
@@ -0,0 +1,54 @@
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+use signal_hook::flag;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How often sleeping loops look at the flags
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// SIGINT/SIGTERM ask the main loop to stop, SIGHUP to reload the config
+/// The handlers only set flags, the main loop acts on them
+pub struct Signals {
+    shutdown: Arc<AtomicBool>,
+    reload: Arc<AtomicBool>,
+}
+
+impl Signals {
+    pub fn register() -> io::Result<Self> {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let reload = Arc::new(AtomicBool::new(false));
+
+        for signal in [SIGINT, SIGTERM] {
+            // A second Ctrl+C while shutting down kills the process right away
+            flag::register_conditional_shutdown(signal, 1, Arc::clone(&shutdown))?;
+            flag::register(signal, Arc::clone(&shutdown))?;
+        }
+        flag::register(SIGHUP, Arc::clone(&reload))?;
+
+        Ok(Self { shutdown, reload })
+    }
+
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    /// True once per SIGHUP
+    pub fn take_reload(&self) -> bool {
+        self.reload.swap(false, Ordering::Relaxed)
+    }
+
+    /// Sleep, but wake up early when a signal arrives
+    pub fn sleep(&self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while !self.shutdown_requested() && !self.reload.load(Ordering::Relaxed) {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            thread::sleep(left.min(POLL_INTERVAL));
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::config::StopConfig;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+// Data younger than this many fetch intervals is fresh (allows two missed fetches)
+const FRESH_INTERVALS: i64 = 3;
+// Older than this the countdowns can't be trusted at all (5 min at the default 20s)
+const STALE_INTERVALS: i64 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusPosition {
+    #[default]
+    Top,
+    Bottom,
+}
+
+/// Optional status bar, `[display.status_bar]` in the config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatusBarConfig {
+    pub position: StatusPosition,
+    /// Local time (Europe/Berlin)
+    pub clock: bool,
+    /// Coloured dot: green = fresh, yellow = stale, red = no data
+    pub age: bool,
+    /// Station name shown between clock and age dot, defaults to the first `[[stops]] name`
+    pub station: Option<String>,
+}
+
+impl Default for StatusBarConfig {
+    fn default() -> Self {
+        Self {
+            position: StatusPosition::Top,
+            clock: true,
+            age: true,
+            station: None,
+        }
+    }
+}
+
+/// How much the departures on screen can be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    Fresh,
+    Stale,
+    Offline,
+}
+
+impl Freshness {
+    /// Classify data by the time of the last successful fetch
+    /// `interval` is `fetch.interval` in seconds, the thresholds scale with it
+    pub fn from_last_success(now: DateTime<Utc>, last_success: Option<DateTime<Utc>>, interval: u64) -> Self {
+        let Some(last_success) = last_success else {
+            return Freshness::Offline;
+        };
+
+        let age = now.signed_duration_since(last_success).num_seconds();
+        let interval = i64::try_from(interval).unwrap_or(i64::MAX);
+        if age <= interval.saturating_mul(FRESH_INTERVALS) {
+            Freshness::Fresh
+        } else if age <= interval.saturating_mul(STALE_INTERVALS) {
+            Freshness::Stale
+        } else {
+            Freshness::Offline
+        }
+    }
+}
+
+/// Everything the status bar shows, compared between frames to redraw only on change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusLine {
+    pub clock: Option<String>,
+    pub station: Option<String>,
+    pub freshness: Option<Freshness>,
+}
+
+impl StatusLine {
+    /// `interval` is `fetch.interval`, see `Freshness::from_last_success`
+    pub fn new(
+        config: &StatusBarConfig,
+        stops: &[StopConfig],
+        now: DateTime<Tz>,
+        last_success: Option<DateTime<Utc>>,
+        interval: u64,
+    ) -> Self {
+        Self {
+            clock: config.clock.then(|| now.format("%H:%M").to_string()),
+            station: config.station.clone().or_else(|| stops.iter().find_map(|stop| stop.name.clone())),
+            freshness: config
+                .age
+                .then(|| Freshness::from_last_success(now.with_timezone(&Utc), last_success, interval)),
+        }
+    }
+}
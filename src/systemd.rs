@@ -0,0 +1,79 @@
+use crate::departure::Departure;
+use chrono::DateTime;
+use chrono_tz::Tz;
+use sd_notify::NotifyState;
+use std::error::Error;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// sd_notify messages for `Type=notify` units
+/// Everything is a no-op when not started by systemd (no $NOTIFY_SOCKET)
+pub struct Systemd {
+    /// Half of WatchdogSec, None if the unit has no watchdog
+    watchdog_interval: Option<Duration>,
+    last_ping: Option<Instant>,
+}
+
+impl Systemd {
+    pub fn new() -> Self {
+        let mut usec = 0;
+        let watchdog_interval = sd_notify::watchdog_enabled(false, &mut usec)
+            .then(|| Duration::from_micros(usec / 2));
+        if let Some(interval) = watchdog_interval {
+            info!(interval_ms = interval.as_millis(), "systemd watchdog enabled");
+        }
+
+        Self { watchdog_interval, last_ping: None }
+    }
+
+    pub fn ready(&self) {
+        notify(&[NotifyState::Ready]);
+    }
+
+    pub fn reloading(&self) {
+        notify(&[NotifyState::Reloading]);
+    }
+
+    pub fn stopping(&self) {
+        notify(&[NotifyState::Stopping]);
+    }
+
+    /// Free text shown by `systemctl status`
+    pub fn status(&self, text: &str) {
+        notify(&[NotifyState::Status(text)]);
+    }
+
+    /// Call from the main loop, pings at half the watchdog interval
+    /// A hung loop stops pinging and systemd restarts the service
+    pub fn watchdog(&mut self) {
+        let Some(interval) = self.watchdog_interval else {
+            return;
+        };
+        if self.last_ping.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        notify(&[NotifyState::Watchdog]);
+        self.last_ping = Some(Instant::now());
+    }
+}
+
+impl Default for Systemd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn notify(state: &[NotifyState]) {
+    if let Err(e) = sd_notify::notify(false, state) {
+        debug!("sd_notify failed: {}", e);
+    }
+}
+
+/// STATUS text for the outcome of a fetch
+pub fn fetch_status(result: &Result<Vec<Departure>, Box<dyn Error>>, now: DateTime<Tz>) -> String {
+    let time = now.format("%H:%M:%S");
+    match result {
+        Ok(departures) => format!("{} departures, last fetch {}", departures.len(), time),
+        Err(e) => format!("API error at {}: {}", time, e),
+    }
+}
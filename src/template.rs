@@ -0,0 +1,283 @@
+use crate::departure::Departure;
+use crate::font::{FontMetrics, TextMeasure};
+use crate::locale::Locale;
+use crate::schedule::TIMEZONE;
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+
+/// Words the fields fill in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Words<'a> {
+    /// `{min}` and `{due}` at zero minutes
+    pub now: &'a str,
+    /// Unit after the minutes in `{due}`
+    pub minutes: &'a str,
+    /// `{min}` and `{due}` of a cancelled departure
+    pub cancelled: &'a str,
+}
+
+/// English, see `Locale::words`
+impl Default for Words<'_> {
+    fn default() -> Self {
+        Locale::default().words()
+    }
+}
+
+/// Departure field in a template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Line,
+    Dest,
+    /// "3", or "now" when due, "cancelled" when cancelled
+    Min,
+    /// "3 min", or "now" when due, "cancelled" when cancelled
+    Due,
+    /// "+2" / "-1", empty when on time or unknown
+    Delay,
+    Platform,
+    /// Timetable departure time, "HH:MM" Berlin time
+    Planned,
+}
+
+const FIELDS: &str = "line, dest, min, due, delay, platform, planned";
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        let field = match name {
+            "line" => Field::Line,
+            "dest" => Field::Dest,
+            "min" => Field::Min,
+            "due" => Field::Due,
+            "delay" => Field::Delay,
+            "platform" => Field::Platform,
+            "planned" => Field::Planned,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    fn value(self, departure: &Departure, words: &Words) -> String {
+        match self {
+            Field::Line => departure.line.clone(),
+            Field::Dest => departure.destination_label(),
+            Field::Min | Field::Due if departure.cancelled => words.cancelled.to_string(),
+            Field::Min if departure.minutes == 0 => words.now.to_string(),
+            Field::Min => departure.minutes.to_string(),
+            Field::Due if departure.minutes == 0 => words.now.to_string(),
+            Field::Due => format!("{} {}", departure.minutes, words.minutes),
+            Field::Delay => match departure.delay {
+                Some(delay) if delay != 0 => format!("{:+}", delay),
+                _ => String::new(),
+            },
+            Field::Platform => departure.platform.clone().unwrap_or_default(),
+            Field::Planned => departure
+                .planned
+                .map(|t| t.with_timezone(&TIMEZONE).format("%H:%M").to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Field {
+        field: Field,
+        /// Padded and cut to this many characters
+        width: Option<usize>,
+        align: Align,
+    },
+}
+
+/// Departure text like "{line} → {dest:12} {min}'"
+/// `{field:12}` pads or cuts to 12 characters, `{field:>3}` aligns right,
+/// `{{` and `}}` are literal braces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(format!("Unclosed '{{' in template '{}'", source)),
+                        }
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Self::parse_field(&placeholder, source)?);
+                }
+                '}' => return Err(format!("Unmatched '}}' in template '{}', use '}}}}'", source)),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { source: source.to_string(), parts })
+    }
+
+    /// "dest", "dest:12", "min:>3"
+    fn parse_field(placeholder: &str, source: &str) -> Result<Part, String> {
+        let (name, spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec.trim())),
+            None => (placeholder.trim(), None),
+        };
+        let field = Field::parse(name)
+            .ok_or_else(|| format!("Unknown field '{{{}}}' in template '{}', expected one of {}", name, source, FIELDS))?;
+
+        let Some(spec) = spec else {
+            return Ok(Part::Field { field, width: None, align: Align::Left });
+        };
+        let (align, width) = match spec.strip_prefix('>') {
+            Some(width) => (Align::Right, width),
+            None => (Align::Left, spec.strip_prefix('<').unwrap_or(spec)),
+        };
+        let width = width
+            .parse()
+            .map_err(|_| format!("Invalid width '{}' in template '{}'", spec, source))?;
+        Ok(Part::Field { field, width: Some(width), align })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn render(&self, departure: &Departure, words: &Words) -> String {
+        self.render_with_dest(departure, words, &departure.destination_label())
+    }
+
+    /// Like `render`, shortening only the destination until the text fits `max_width`
+    /// `truncate` cuts a text to a width, in whatever unit `measure` counts
+    pub fn render_fitted(
+        &self,
+        departure: &Departure,
+        words: &Words,
+        max_width: u32,
+        measure: &dyn TextMeasure,
+        truncate: impl Fn(&str, u32) -> String,
+    ) -> String {
+        let full = self.render(departure, words);
+        if measure.text_width(&full) <= max_width {
+            return full;
+        }
+
+        let overhead = measure.text_width(&self.render_with_dest(departure, words, ""));
+        if overhead >= max_width {
+            // Can't fit anything, cut the whole text
+            return truncate(&full, max_width);
+        }
+
+        let destination = truncate(&departure.destination_label(), max_width - overhead);
+        self.render_with_dest(departure, words, &destination)
+    }
+
+    fn render_with_dest(&self, departure: &Departure, words: &Words, destination: &str) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Field { field, width, align } => {
+                    let value = match field {
+                        Field::Dest => destination.to_string(),
+                        field => field.value(departure, words),
+                    };
+                    match (width, align) {
+                        (None, _) => out.push_str(&value),
+                        (Some(width), align) => {
+                            let value: String = value.chars().take(*width).collect();
+                            let value = match align {
+                                Align::Left => format!("{:<width$}", value),
+                                Align::Right => format!("{:>width$}", value),
+                            };
+                            out.push_str(&value);
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Measures text in characters for `render_fitted` on a console
+pub struct CharCount;
+
+impl TextMeasure for CharCount {
+    fn char_width(&self, _c: char) -> u32 {
+        1
+    }
+
+    fn metrics(&self) -> FontMetrics {
+        FontMetrics { char_width: 1, height: 1, baseline: 1 }
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Template {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Template::parse(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Departure texts, `[format]` in the config
+/// The LED panel only takes its minutes from here, badge and destination are drawn to fit
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FormatConfig {
+    /// Console output and logs, "S3 Erkner 2 min"
+    pub departure: Template,
+    /// Minutes column of the board page and the terminal UI, right-aligned
+    pub board_minutes: Template,
+    /// Below the destination on the departure page
+    pub departure_minutes: Template,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            departure: default_template(),
+            board_minutes: Template::parse("{min}'").expect("valid template"),
+            departure_minutes: Template::parse("{due}").expect("valid template"),
+        }
+    }
+}
+
+/// "{line} {dest} {due}", what `Departure::format` has always printed
+pub fn default_template() -> Template {
+    Template::parse("{line} {dest} {due}").expect("valid template")
+}
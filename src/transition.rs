@@ -0,0 +1,85 @@
+use serde::Deserialize;
+
+/// Animation when a page (or the next departure) appears
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transition {
+    /// Instant redraw, cheapest on a Pi Zero
+    #[default]
+    None,
+    /// Old page moves out at the top, new one comes in from below
+    SlideUp,
+    /// Old page fades to black, then the new one fades in
+    Fade,
+    /// Black bar sweeps the old page away left to right, then uncovers the new one
+    Wipe,
+}
+
+/// How one page is drawn in a transition frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layer {
+    /// Vertical shift in pixels
+    pub y_offset: i32,
+    /// 255 = full brightness, 0 = black
+    pub fade: u8,
+}
+
+impl Layer {
+    pub const STILL: Layer = Layer { y_offset: 0, fade: 255 };
+}
+
+/// Everything drawn for one frame of a transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionFrame {
+    pub old: Option<Layer>,
+    pub new: Option<Layer>,
+    /// Columns [start, end) painted black after drawing the layers
+    pub blank_columns: Option<(i32, i32)>,
+}
+
+/// Number of frames for a transition, at least one
+pub fn frame_count(duration_ms: u64, fps: u32) -> u32 {
+    ((duration_ms * fps as u64 / 1000) as u32).max(1)
+}
+
+impl Transition {
+    /// Frame at `progress` (0.0 = old page only, 1.0 = new page only)
+    pub fn frame(self, progress: f32, width: i32, height: i32) -> TransitionFrame {
+        let progress = progress.clamp(0.0, 1.0);
+        // First and second half for the two-phase transitions
+        let out_phase = progress < 0.5;
+        let half = if out_phase { progress * 2.0 } else { progress * 2.0 - 1.0 };
+
+        match self {
+            Transition::None => TransitionFrame {
+                old: None,
+                new: Some(Layer::STILL),
+                blank_columns: None,
+            },
+            Transition::SlideUp => {
+                let shift = (progress * height as f32).round() as i32;
+                TransitionFrame {
+                    old: Some(Layer { y_offset: -shift, fade: 255 }),
+                    new: Some(Layer { y_offset: height - shift, fade: 255 }),
+                    blank_columns: None,
+                }
+            }
+            Transition::Fade => {
+                let layer = |level: f32| Some(Layer { y_offset: 0, fade: (level * 255.0).round() as u8 });
+                if out_phase {
+                    TransitionFrame { old: layer(1.0 - half), new: None, blank_columns: None }
+                } else {
+                    TransitionFrame { old: None, new: layer(half), blank_columns: None }
+                }
+            }
+            Transition::Wipe => {
+                let edge = (half * width as f32).round() as i32;
+                if out_phase {
+                    TransitionFrame { old: Some(Layer::STILL), new: None, blank_columns: Some((0, edge)) }
+                } else {
+                    TransitionFrame { old: None, new: Some(Layer::STILL), blank_columns: Some((edge, width)) }
+                }
+            }
+        }
+    }
+}
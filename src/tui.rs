@@ -0,0 +1,173 @@
+use crate::config::StopConfig;
+use crate::departure::Departure;
+use crate::locale::Locale;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, VecDeque};
+use std::error::Error;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+// Log lines kept for the log pane
+const LOG_LINES: usize = 200;
+
+/// Kind of transport, what the filter keys toggle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Product {
+    UBahn,
+    SBahn,
+    /// Trams and everything else that gets through the API filter (metro buses)
+    Tram,
+}
+
+impl Product {
+    pub const ALL: [Product; 3] = [Product::UBahn, Product::SBahn, Product::Tram];
+
+    pub fn for_line(line: &str) -> Self {
+        let mut chars = line.chars();
+        let numbered = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+        match chars.next() {
+            Some('U') if numbered(chars.next()) => Product::UBahn,
+            Some('S') if numbered(chars.next()) => Product::SBahn,
+            _ => Product::Tram,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Product::UBahn => "U-Bahn",
+            Product::SBahn => "S-Bahn",
+            Product::Tram => "Tram",
+        }
+    }
+}
+
+/// What the terminal board shows, independent of the terminal itself
+pub struct TuiState {
+    stops: Vec<StopConfig>,
+    /// Index into stops, None shows all stops merged
+    selected: Option<usize>,
+    hidden: BTreeSet<Product>,
+    departures: Vec<Departure>,
+    locale: Locale,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl TuiState {
+    pub fn new(stops: Vec<StopConfig>, locale: Locale) -> Self {
+        Self {
+            stops,
+            selected: None,
+            hidden: BTreeSet::new(),
+            departures: Vec::new(),
+            locale,
+            last_success: None,
+            last_error: None,
+        }
+    }
+
+    /// A failed fetch keeps the last board, like the LED display does
+    pub fn update(&mut self, result: Result<Vec<Departure>, Box<dyn Error>>, now: DateTime<Utc>) {
+        match result {
+            Ok(departures) => {
+                self.departures = departures;
+                self.last_success = Some(now);
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(e.to_string()),
+        }
+    }
+
+    /// All stops, then every stop on its own, then all stops again
+    /// A single stop has nothing to switch
+    pub fn next_stop(&mut self) {
+        if self.stops.len() < 2 {
+            return;
+        }
+        self.selected = match self.selected {
+            None => Some(0),
+            Some(i) if i + 1 < self.stops.len() => Some(i + 1),
+            Some(_) => None,
+        };
+    }
+
+    pub fn previous_stop(&mut self) {
+        if self.stops.len() < 2 {
+            return;
+        }
+        self.selected = match self.selected {
+            None => Some(self.stops.len() - 1),
+            Some(0) => None,
+            Some(i) => Some(i - 1),
+        };
+    }
+
+    pub fn toggle(&mut self, product: Product) {
+        if !self.hidden.remove(&product) {
+            self.hidden.insert(product);
+        }
+    }
+
+    pub fn is_shown(&self, product: Product) -> bool {
+        !self.hidden.contains(&product)
+    }
+
+    /// Name of the selected stop, "All stops" (localised) when merged
+    pub fn stop_label(&self) -> String {
+        match self.selected.map(|i| &self.stops[i]) {
+            Some(stop) => stop.name.clone().unwrap_or_else(|| stop.id.clone()),
+            None if self.stops.len() == 1 => {
+                let stop = &self.stops[0];
+                stop.name.clone().unwrap_or_else(|| stop.id.clone())
+            }
+            None => self.locale.strings().all_stops.to_string(),
+        }
+    }
+
+    /// Departures of the selected stop with the hidden products filtered out
+    pub fn visible(&self) -> Vec<&Departure> {
+        let stop = self.selected.map(|i| self.stops[i].id.as_str());
+        self.departures
+            .iter()
+            .filter(|d| stop.is_none() || d.stop.as_deref() == stop)
+            .filter(|d| self.is_shown(Product::for_line(&d.line)))
+            .collect()
+    }
+}
+
+/// Log lines for the log pane, tracing writes here instead of stderr
+/// Cloning shares the buffer
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogBuffer {
+    pub fn push(&self, line: &str) {
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == LOG_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line.to_string());
+    }
+
+    /// The newest `count` lines, oldest first
+    pub fn last(&self, count: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().skip(lines.len().saturating_sub(count)).cloned().collect()
+    }
+}
+
+impl io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The fmt layer writes one event per call
+        for line in String::from_utf8_lossy(buf).lines().filter(|l| !l.trim().is_empty()) {
+            self.push(line);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use crate::api::fetch_stops;
+use crate::colors::{resolve_line_color, Rgb};
+use crate::config::{BoardMode, Config};
+use crate::departure::Departure;
+use crate::schedule;
+use crate::signals::Signals;
+use crate::tui::{LogBuffer, Product, TuiState};
+use chrono::Utc;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::error::Error;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+use tracing::info;
+
+// How often keys and new boards are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+// Log pane height including its border
+const LOG_HEIGHT: u16 = 8;
+
+/// Errors are sent as strings, Box<dyn Error> can't cross threads
+type FetchResult = Result<Vec<Departure>, String>;
+
+/// Run the terminal board until q, Ctrl+C or SIGTERM
+/// Fetching happens on its own thread so a slow API doesn't freeze the keys
+pub fn run(config: &Config, logs: LogBuffer, signals: &Signals) -> Result<(), Box<dyn Error>> {
+    let (refresh, results) = spawn_fetcher(config)?;
+    let mut state = TuiState::new(config.stops.clone(), config.locale);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, config, &mut state, &logs, signals, &refresh, &results);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    config: &Config,
+    state: &mut TuiState,
+    logs: &LogBuffer,
+    signals: &Signals,
+    refresh: &mpsc::Sender<()>,
+    results: &Receiver<FetchResult>,
+) -> Result<(), Box<dyn Error>> {
+    while !signals.shutdown_requested() {
+        for result in results.try_iter() {
+            state.update(result.map_err(Into::into), Utc::now());
+        }
+
+        terminal.draw(|frame| draw(frame, config, state, logs))?;
+
+        if !event::poll(POLL_INTERVAL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            // Raw mode turns Ctrl+C into a key instead of SIGINT
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Tab | KeyCode::Right => state.next_stop(),
+            KeyCode::BackTab | KeyCode::Left => state.previous_stop(),
+            KeyCode::Char('u') => state.toggle(Product::UBahn),
+            KeyCode::Char('s') => state.toggle(Product::SBahn),
+            KeyCode::Char('t') => state.toggle(Product::Tram),
+            KeyCode::Char('r') => {
+                info!("Refresh requested");
+                let _ = refresh.send(());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Fetch thread: every fetch.interval, or right away on a refresh request
+fn spawn_fetcher(config: &Config) -> Result<(mpsc::Sender<()>, Receiver<FetchResult>), Box<dyn Error>> {
+    let (refresh_tx, refresh_rx) = mpsc::channel();
+    let (result_tx, result_rx) = mpsc::channel();
+    let stops = config.stops.clone();
+    let fetch = config.fetch.clone();
+
+    thread::Builder::new().name("fetch".to_string()).spawn(move || {
+        let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(10)).build();
+        loop {
+            let result = fetch_stops(&agent, &stops, &fetch).map_err(|e| e.to_string());
+            if result_tx.send(result).is_err() {
+                break;
+            }
+            match refresh_rx.recv_timeout(Duration::from_secs(fetch.interval)) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    })?;
+
+    Ok((refresh_tx, result_rx))
+}
+
+fn draw(frame: &mut Frame, config: &Config, state: &TuiState, logs: &LogBuffer) {
+    let [header, board, log, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(LOG_HEIGHT),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let strings = config.locale.strings();
+    frame.render_widget(Paragraph::new(header_line(state, config)), header);
+    draw_board(frame, board, config, state);
+
+    let lines: Vec<Line> = logs
+        .last(usize::from(LOG_HEIGHT.saturating_sub(2)))
+        .into_iter()
+        .map(|l| Line::from(l).dark_gray())
+        .collect();
+    let title = format!(" {} ", strings.log);
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), log);
+
+    frame.render_widget(Paragraph::new(strings.keys).dark_gray(), footer);
+}
+
+/// Stop name, arrivals mode, product filter, clock and data age or the last error
+fn header_line(state: &TuiState, config: &Config) -> Line<'static> {
+    let locale = config.locale;
+    let mut spans = vec![Span::from(format!(" {} ", state.stop_label())).bold().reversed(), Span::from("  ")];
+    if config.fetch.mode == BoardMode::Arrivals {
+        spans.push(Span::from(locale.strings().arrivals).bold());
+        spans.push(Span::from("  "));
+    }
+    for product in Product::ALL {
+        let style = if state.is_shown(product) {
+            Style::new().green()
+        } else {
+            Style::new().dark_gray().add_modifier(Modifier::CROSSED_OUT)
+        };
+        spans.push(Span::styled(product.label(), style));
+        spans.push(Span::from(" "));
+    }
+
+    let now = schedule::now_local();
+    spans.push(Span::from(format!(" {} ", now.format("%H:%M:%S"))));
+    match (&state.last_error, state.last_success) {
+        (Some(error), _) => spans.push(Span::from(error.clone()).red()),
+        (None, Some(last)) => {
+            let age = Utc::now().signed_duration_since(last).num_seconds();
+            spans.push(Span::from(locale.updated_ago(age)).dark_gray());
+        }
+        (None, None) => spans.push(Span::from(locale.strings().fetching).dark_gray()),
+    }
+    Line::from(spans)
+}
+
+fn draw_board(frame: &mut Frame, area: Rect, config: &Config, state: &TuiState) {
+    let strings = config.locale.strings();
+    let words = config.locale.words();
+    let departures = state.visible();
+    if departures.is_empty() {
+        let text = if state.last_success.is_some() { strings.no_departures } else { strings.waiting };
+        frame.render_widget(Paragraph::new(text).centered().block(Block::bordered()), area);
+        return;
+    }
+
+    let rows = departures.iter().map(|departure| {
+        let colors = resolve_line_color(&departure.line, departure.color, &config.display.line_colors);
+        let badge = Span::from(format!(" {} ", departure.line))
+            .bg(color(colors.bg))
+            .fg(color(colors.fg))
+            .bold();
+
+        let minutes_text = format!("{:>3}", config.format.board_minutes.render(departure, &words));
+        let minutes = match departure.urgency {
+            _ if departure.cancelled => Span::from(strings.cancelled).red(),
+            Some(urgency) => Span::from(minutes_text).fg(color(urgency.color())),
+            None => Span::from(minutes_text),
+        };
+
+        let delay = match departure.delay {
+            Some(delay) if delay > 0 => Span::from(format!("+{}", delay)).red(),
+            Some(delay) if delay < 0 => Span::from(delay.to_string()).green(),
+            _ => Span::from(""),
+        };
+
+        Row::new([
+            Cell::from(badge),
+            Cell::from(departure.destination_label()),
+            Cell::from(minutes),
+            Cell::from(delay),
+            Cell::from(departure.warnings.join("; ")).yellow(),
+        ])
+    });
+
+    let widths = [
+        Constraint::Length(6),
+        Constraint::Fill(2),
+        Constraint::Length(9),
+        Constraint::Length(10),
+        Constraint::Fill(3),
+    ];
+    let table = Table::new(rows, widths)
+        .header(Row::new([strings.line, strings.destination, strings.min, strings.delay, strings.remarks]).bold())
+        .column_spacing(2)
+        .block(Block::bordered());
+    frame.render_widget(table, area);
+}
+
+fn color(rgb: Rgb) -> Color {
+    Color::Rgb(rgb.red, rgb.green, rgb.blue)
+}
@@ -0,0 +1,36 @@
+use crate::colors::Rgb;
+use serde::{Deserialize, Serialize};
+
+/// Whether a departure can still be caught, given the walk to the stop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Urgency {
+    /// Gone before we get there, hidden from the board
+    TooLate,
+    /// Just enough time, leave right now
+    LeaveNow,
+    /// Plenty of time
+    Relaxed,
+}
+
+impl Urgency {
+    /// `walking_time`: minutes to the stop, `leave_now`: minutes of slack that still count as urgent
+    pub fn classify(minutes: u32, walking_time: u32, leave_now: u32) -> Self {
+        if minutes < walking_time {
+            Urgency::TooLate
+        } else if minutes <= walking_time + leave_now {
+            Urgency::LeaveNow
+        } else {
+            Urgency::Relaxed
+        }
+    }
+
+    /// Colour of the minutes on the board
+    pub fn color(self) -> Rgb {
+        match self {
+            Urgency::TooLate => Rgb::new(80, 80, 80),
+            Urgency::LeaveNow => Rgb::new(255, 0, 0),
+            Urgency::Relaxed => Rgb::new(0, 220, 0),
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use rusty_bvg::{fetch_warschauer_str, Departure};
+use rusty_bvg::api::parse_board;
+use rusty_bvg::{fetch_stops_with, fetch_warschauer_str, BoardMode, Departure, FetchConfig, StopConfig};
 
 #[test]
 fn test_api_fetch_exists() {
@@ -25,7 +26,7 @@ fn test_departure_format() {
 
 #[test]
 fn test_departure_sorting() {
-    let mut departures = vec![
+    let mut departures = [
         Departure::new("S3", "Erkner", 10),
         Departure::new("U1", "Warschauer Str.", 2),
         Departure::new("S5", "Strausberg", 5),
@@ -54,7 +55,7 @@ fn test_time_calculation() {
         let diff = departure_utc.signed_duration_since(now);
         let minutes = diff.num_minutes();
         
-        assert!(minutes >= 4 && minutes <= 6); // Allow 1 minute tolerance
+        assert!((4..=6).contains(&minutes)); // Allow 1 minute tolerance
     }
 }
 
@@ -66,67 +67,15 @@ fn test_departure_format_truncated() {
     assert!(truncated.starts_with("S5 "));
 }
 
-#[test]
-fn test_destination_cleaning_berlin_suffix() {
-    // Test removing " (Berlin)" suffix
-    let mut destination = "Springpfuhl (Berlin)".to_string();
-    destination = destination.replace(" (Berlin)", "");
-    assert_eq!(destination, "Springpfuhl");
-}
-
-#[test]
-fn test_destination_cleaning_s_prefix() {
-    // Test removing "S " prefix
-    let mut destination = "S Erkner Bhf".to_string();
-    if destination.starts_with("S ") {
-        destination = destination[2..].to_string();
-    }
-    assert_eq!(destination, "Erkner Bhf");
-}
-
-#[test]
-fn test_destination_cleaning_u_prefix() {
-    // Test removing "U " prefix
-    let mut destination = "U Berliner Str.".to_string();
-    if destination.starts_with("U ") {
-        destination = destination[2..].to_string();
-    }
-    assert_eq!(destination, "Berliner Str.");
-}
-
-#[test]
-fn test_destination_cleaning_bhf_suffix() {
-    // Test removing " Bhf" suffix
-    let mut destination = "Potsdam Hauptbahnhof Bhf".to_string();
-    destination = destination.replace(" Bhf", "");
-    assert_eq!(destination, "Potsdam Hauptbahnhof");
-}
-
 #[test]
 fn test_destination_cleaning_combined() {
-    // Test all cleaning rules combined
-    let mut destination = "S Spandau Bhf (Berlin)".to_string();
-    
-    destination = destination.replace(" (Berlin)", "");
-    if destination.starts_with("S ") {
-        destination = destination[2..].to_string();
-    }
-    destination = destination.replace(" Bhf", "");
-    
-    assert_eq!(destination, "Spandau");
-}
-
-#[test]
-fn test_destination_cleaning_ringbahn_symbols() {
-    // Test removing Ringbahn direction symbols
-    let mut destination1 = "Ringbahn S42 ⟲".to_string();
-    let mut destination2 = "Ringbahn S41 ⟳".to_string();
-    
-    destination1 = destination1.replace(" ⟲", "");
-    destination2 = destination2.replace(" ⟳", "");
-    
-    assert_eq!(destination1, "Ringbahn S42");
-    assert_eq!(destination2, "Ringbahn S41");
+    // Fetched boards go through the default cleanup rules
+    let body = r#"{"departures": [
+        {"line": {"name": "S3"}, "direction": "S Spandau Bhf (Berlin)", "when": "2025-03-10T09:04:00+01:00"}
+    ]}"#;
+    let now = chrono::DateTime::parse_from_rfc3339("2025-03-10T09:00:00+01:00").unwrap().with_timezone(&chrono::Utc);
+    let departures = parse_board(body, "900120003", BoardMode::Departures, &FetchConfig::default(), now).unwrap();
+    assert_eq!(departures[0].destination, "Spandau");
 }
 
 #[test]
@@ -196,10 +145,10 @@ fn test_time_filtering_minimum_one_minute() {
     let minutes_60 = 60;
     let minutes_61 = 61;
     
-    assert!(!(minutes_0 >= 1 && minutes_0 <= 60), "0 min should be filtered");
-    assert!(minutes_1 >= 1 && minutes_1 <= 60, "1 min should be included");
-    assert!(minutes_60 >= 1 && minutes_60 <= 60, "60 min should be included");
-    assert!(!(minutes_61 >= 1 && minutes_61 <= 60), "61 min should be filtered");
+    assert!(!(1..=60).contains(&minutes_0), "0 min should be filtered");
+    assert!((1..=60).contains(&minutes_1), "1 min should be included");
+    assert!((1..=60).contains(&minutes_60), "60 min should be included");
+    assert!(!(1..=60).contains(&minutes_61), "61 min should be filtered");
 }
 
 #[test]
@@ -219,3 +168,14 @@ fn test_destination_filtering_current_station() {
     }
 }
 
+
+#[test]
+fn test_fetch_stops_runs_callback_per_stop() {
+    // Invalid IDs fail before any request, no network needed
+    let agent = ureq::AgentBuilder::new().build();
+    let stops = [StopConfig::new("abc"), StopConfig::new(""), StopConfig::new("x1")];
+    let mut pings = 0;
+    let result = fetch_stops_with(&agent, &stops, &FetchConfig::default(), || pings += 1);
+    assert!(result.is_err());
+    assert_eq!(pings, 3);
+}
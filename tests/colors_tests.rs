@@ -0,0 +1,73 @@
+use rusty_bvg::colors::{resolve_line_color, vbb_line_color, BadgeShape, LineColor, Rgb};
+use rusty_bvg::Config;
+use std::collections::HashMap;
+
+#[test]
+fn test_rgb_from_hex() {
+    assert_eq!(Rgb::from_hex("#7DAD4C"), Some(Rgb::new(0x7D, 0xAD, 0x4C)));
+    assert_eq!(Rgb::from_hex("ffffff"), Some(Rgb::new(255, 255, 255)));
+    assert_eq!(Rgb::from_hex("#f0a"), Some(Rgb::new(255, 0, 170)));
+    assert_eq!(Rgb::from_hex("#12345"), None);
+    assert_eq!(Rgb::from_hex("#zzzzzz"), None);
+}
+
+#[test]
+fn test_vbb_table_known_lines() {
+    assert_eq!(vbb_line_color("U1").unwrap().bg, Rgb::new(0x7D, 0xAD, 0x4C));
+    assert_eq!(vbb_line_color("U2").unwrap().bg, Rgb::new(0xDA, 0x42, 0x1E));
+    assert_eq!(vbb_line_color("M10").unwrap().bg, Rgb::new(0xBE, 0x14, 0x14));
+    // Metro bus, not a tram
+    assert_eq!(vbb_line_color("M29"), None);
+}
+
+#[test]
+fn test_resolve_priority() {
+    let api = LineColor::new(Rgb::new(1, 2, 3), Rgb::new(4, 5, 6));
+    let custom = LineColor::new(Rgb::new(9, 9, 9), Rgb::new(0, 0, 0));
+    let mut overrides = HashMap::new();
+
+    // Table when the API has nothing
+    assert_eq!(resolve_line_color("U1", None, &overrides), vbb_line_color("U1").unwrap());
+    // API beats the table
+    assert_eq!(resolve_line_color("U1", Some(api), &overrides), api);
+    // Config beats everything
+    overrides.insert("U1".to_string(), custom);
+    assert_eq!(resolve_line_color("U1", Some(api), &overrides), custom);
+}
+
+#[test]
+fn test_badge_shape() {
+    assert_eq!(BadgeShape::for_line("S5"), BadgeShape::Pill);
+    assert_eq!(BadgeShape::for_line("U1"), BadgeShape::Rect);
+    assert_eq!(BadgeShape::for_line("M10"), BadgeShape::Rect);
+}
+
+#[test]
+fn test_config_line_color_override() {
+    let config = Config::from_toml(
+        r##"
+        [display]
+        width = 64
+
+        [display.line_colors]
+        U1 = { bg = "#000000", fg = "#ffffff" }
+        "##,
+    )
+    .unwrap();
+
+    let u1 = config.display.line_colors.get("U1").unwrap();
+    assert_eq!(u1.bg, Rgb::new(0, 0, 0));
+    assert_eq!(u1.fg, Rgb::new(255, 255, 255));
+    assert_eq!(config.display.height, 32);
+}
+
+#[test]
+fn test_config_rejects_bad_colour() {
+    let result = Config::from_toml(
+        r##"
+        [display.line_colors]
+        U1 = { bg = "green", fg = "#ffffff" }
+        "##,
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,54 @@
+use rusty_bvg::{Config, DisplayConfig};
+
+#[test]
+fn test_default_config() {
+    let config = Config::default();
+    assert_eq!(config.display.canvas_size(), (64, 32));
+    assert!(config.display.validate().is_ok());
+}
+
+#[test]
+fn test_canvas_size_chained_panels() {
+    let config = Config::from_toml("[display]\nchain_length = 2").unwrap();
+    assert_eq!(config.display.canvas_size(), (128, 32));
+
+    let config = Config::from_toml("[display]\nwidth = 64\nheight = 64\nchain_length = 2\nparallel = 2").unwrap();
+    assert_eq!(config.display.canvas_size(), (128, 128));
+}
+
+#[test]
+fn test_canvas_size_pixel_mappers() {
+    let display = DisplayConfig {
+        chain_length: 4,
+        pixel_mapper: Some("U-mapper".to_string()),
+        ..DisplayConfig::default()
+    };
+    assert_eq!(display.canvas_size(), (128, 64));
+
+    let display = DisplayConfig {
+        chain_length: 2,
+        pixel_mapper: Some("U-mapper;Rotate:90".to_string()),
+        ..DisplayConfig::default()
+    };
+    assert_eq!(display.canvas_size(), (64, 64));
+
+    let display = DisplayConfig {
+        pixel_mapper: Some("Rotate:180".to_string()),
+        ..DisplayConfig::default()
+    };
+    assert_eq!(display.canvas_size(), (64, 32));
+}
+
+#[test]
+fn test_config_rejects_invalid_geometry() {
+    assert!(Config::from_toml("[display]\npwm_bits = 12").is_err());
+    assert!(Config::from_toml("[display]\nbrightness = 0").is_err());
+    assert!(Config::from_toml("[display]\nparallel = 4").is_err());
+    assert!(Config::from_toml("[display]\nchain_length = 0").is_err());
+}
+
+#[test]
+fn test_config_rejects_unknown_keys() {
+    // Typos shouldn't be silently ignored
+    assert!(Config::from_toml("[display]\nchain_lenght = 2").is_err());
+}
@@ -0,0 +1,213 @@
+use chrono::{Duration, TimeZone, Utc};
+use rusty_bvg::control::{handle, parse_command, read_body, BoardState, Command, Control, HttpConfig, Request, SharedState};
+use rusty_bvg::pages::{PageKind, Priority};
+use rusty_bvg::Departure;
+use std::error::Error;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration as StdDuration;
+
+fn state_with(departures: Vec<Departure>) -> SharedState {
+    let mut state = BoardState::default();
+    let result: Result<Vec<Departure>, Box<dyn Error>> = Ok(departures);
+    state.record_fetch(&result, Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap(), StdDuration::from_millis(300));
+    Arc::new(Mutex::new(state))
+}
+
+#[test]
+fn test_get_departures() {
+    let state = state_with(vec![Departure::new("U1", "Uhlandstr.", 3)]);
+    let (tx, _rx) = mpsc::channel();
+    let control = Control::new(Some(tx), None);
+    let response = handle(&Request::new("GET", "/departures", ""), &state, &control, Utc::now());
+    assert_eq!(response.status, 200);
+
+    let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json[0]["line"], "U1");
+    assert_eq!(json[0]["minutes"], 3);
+    // Same schema as `--output json`, no internals like the badge colour
+    assert_eq!(json[0]["cancelled"], false);
+    assert!(json[0].get("color").is_none());
+}
+
+#[test]
+fn test_health() {
+    let state = state_with(Vec::new());
+    let failed: Result<Vec<Departure>, Box<dyn Error>> = Err("HTTP error: timeout".into());
+    state.lock().unwrap().record_fetch(&failed, Utc::now(), StdDuration::from_secs(10));
+
+    let (tx, _rx) = mpsc::channel();
+    let control = Control::new(Some(tx), None);
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap() + Duration::seconds(30);
+    let response = handle(&Request::new("GET", "/health", ""), &state, &control, now);
+    let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(json["status"], "ok");
+    assert_eq!(json["age_seconds"], 30);
+    assert_eq!(json["fetches"], 2);
+    assert_eq!(json["fetch_errors"], 1);
+    assert_eq!(json["last_error"], "HTTP error: timeout");
+}
+
+#[test]
+fn test_post_message() {
+    let state = state_with(Vec::new());
+    let (tx, rx) = mpsc::channel();
+    let control = Control::new(Some(tx), None);
+
+    let response = handle(&Request::new("POST", "/message", r#"{"text": " Kuchen in der Küche ", "duration": 60}"#), &state, &control, Utc::now());
+    assert_eq!(response.status, 202);
+    assert_eq!(
+        rx.try_recv().unwrap(),
+        Command::Message {
+            text: "Kuchen in der Küche".to_string(),
+            duration: 60,
+            ttl: 60,
+            priority: Priority::Normal,
+        }
+    );
+
+    assert_eq!(handle(&Request::new("POST", "/message", r#"{"text": ""}"#), &state, &control, Utc::now()).status, 400);
+    assert_eq!(handle(&Request::new("POST", "/message", "not json"), &state, &control, Utc::now()).status, 400);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_post_brightness() {
+    let state = state_with(Vec::new());
+    let (tx, rx) = mpsc::channel();
+    let control = Control::new(Some(tx), None);
+
+    assert_eq!(handle(&Request::new("POST", "/brightness", r#"{"brightness": 40}"#), &state, &control, Utc::now()).status, 202);
+    assert_eq!(rx.try_recv().unwrap(), Command::Brightness(Some(40)));
+    assert_eq!(handle(&Request::new("POST", "/brightness", r#"{"brightness": null}"#), &state, &control, Utc::now()).status, 202);
+    assert_eq!(rx.try_recv().unwrap(), Command::Brightness(None));
+    assert_eq!(handle(&Request::new("POST", "/brightness", r#"{"brightness": 150}"#), &state, &control, Utc::now()).status, 400);
+}
+
+#[test]
+fn test_parse_command() {
+    assert_eq!(
+        parse_command(r#"{"command": "message", "text": "Team lunch 12:30", "duration": 10, "ttl": 3600, "priority": "low"}"#),
+        Ok(Command::Message {
+            text: "Team lunch 12:30".to_string(),
+            duration: 10,
+            ttl: 3600,
+            priority: Priority::Low,
+        })
+    );
+    assert_eq!(
+        parse_command(r#"{"command": "page", "page": "clock"}"#),
+        Ok(Command::ShowPage { kind: PageKind::Clock, duration: 30 })
+    );
+    assert_eq!(parse_command(r#"{"command": "blank"}"#), Ok(Command::Blank(true)));
+    assert_eq!(parse_command(r#"{"command": "blank", "blank": false}"#), Ok(Command::Blank(false)));
+    assert_eq!(parse_command(r#"{"command": "clear_messages"}"#), Ok(Command::ClearMessages));
+    assert_eq!(parse_command(r#"{"command": "brightness", "brightness": 40}"#), Ok(Command::Brightness(Some(40))));
+
+    for invalid in [
+        r#"{"text": "no command"}"#,
+        r#"{"command": "reboot"}"#,
+        r#"{"command": "message", "text": "x", "ttl": 0}"#,
+        r#"{"command": "message", "text": "x", "ttl": 100000}"#,
+        r#"{"command": "message", "text": "x", "priority": "urgent"}"#,
+        r#"{"command": "message", "text": "x", "colour": "red"}"#,
+        r#"{"command": "page", "page": "text"}"#,
+        r#"{"command": "page", "page": "clock", "duration": 0}"#,
+    ] {
+        assert!(parse_command(invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_page_blank_and_clear_endpoints() {
+    let state = state_with(Vec::new());
+    let (tx, rx) = mpsc::channel();
+    let control = Control::new(Some(tx), None);
+
+    assert_eq!(handle(&Request::new("POST", "/page", r#"{"page": "board", "duration": 15}"#), &state, &control, Utc::now()).status, 202);
+    assert_eq!(rx.try_recv().unwrap(), Command::ShowPage { kind: PageKind::Board, duration: 15 });
+    assert_eq!(handle(&Request::new("POST", "/blank", "{}"), &state, &control, Utc::now()).status, 202);
+    assert_eq!(rx.try_recv().unwrap(), Command::Blank(true));
+    assert_eq!(handle(&Request::new("DELETE", "/message", ""), &state, &control, Utc::now()).status, 202);
+    assert_eq!(rx.try_recv().unwrap(), Command::ClearMessages);
+    assert_eq!(handle(&Request::new("POST", "/command", r#"{"command": "blank", "blank": false}"#), &state, &control, Utc::now()).status, 202);
+    assert_eq!(rx.try_recv().unwrap(), Command::Blank(false));
+
+    // The endpoint decides the command
+    assert_eq!(handle(&Request::new("POST", "/blank", r#"{"command": "page", "page": "clock"}"#), &state, &control, Utc::now()).status, 400);
+    assert_eq!(handle(&Request::new("POST", "/page", "[]"), &state, &control, Utc::now()).status, 400);
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn test_unknown_routes() {
+    let state = state_with(Vec::new());
+    let (tx, rx) = mpsc::channel();
+    let control = Control::new(Some(tx), None);
+    assert_eq!(handle(&Request::new("GET", "/nope", ""), &state, &control, Utc::now()).status, 404);
+    assert_eq!(handle(&Request::new("DELETE", "/departures", ""), &state, &control, Utc::now()).status, 405);
+
+    // Main loop gone: commands can't be delivered
+    drop(rx);
+    assert_eq!(handle(&Request::new("POST", "/brightness", r#"{"brightness": 40}"#), &state, &control, Utc::now()).status, 503);
+}
+
+#[test]
+fn test_read_body() {
+    assert_eq!(read_body(&br#"{"brightness": 40}"#[..]).unwrap(), r#"{"brightness": 40}"#);
+    assert_eq!(read_body(&[b' '; 4096][..]).unwrap().len(), 4096);
+
+    // Too long: rejected instead of cut, even where the cut would split a character
+    assert_eq!(read_body(&[b' '; 4097][..]).unwrap_err().status, 413);
+    let umlauts = "ü".repeat(3000);
+    assert_eq!(read_body(umlauts.as_bytes()).unwrap_err().status, 413);
+
+    assert_eq!(read_body(&[0xff, 0xfe][..]).unwrap_err().status, 400);
+    struct Broken;
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("connection reset"))
+        }
+    }
+    assert_eq!(read_body(Broken).unwrap_err().status, 400);
+}
+
+#[test]
+fn test_bearer_token() {
+    let state = state_with(Vec::new());
+    let (tx, rx) = mpsc::channel();
+    let control = Control::new(Some(tx), Some("s3cret".to_string()));
+    let blank = Request::new("POST", "/blank", "{}");
+
+    assert_eq!(handle(&blank, &state, &control, Utc::now()).status, 401);
+    assert_eq!(handle(&blank.with_authorization("Bearer wrong!"), &state, &control, Utc::now()).status, 401);
+    assert_eq!(handle(&blank.with_authorization("s3cret"), &state, &control, Utc::now()).status, 401);
+    assert!(rx.try_recv().is_err());
+
+    assert_eq!(handle(&blank.with_authorization("Bearer s3cret"), &state, &control, Utc::now()).status, 202);
+    assert_eq!(rx.try_recv().unwrap(), Command::Blank(true));
+
+    // Reading needs no token
+    assert_eq!(handle(&Request::new("GET", "/health", ""), &state, &control, Utc::now()).status, 200);
+}
+
+#[test]
+fn test_no_display_has_no_control_routes() {
+    let state = state_with(Vec::new());
+    let control = Control::new(None, None);
+    assert_eq!(handle(&Request::new("POST", "/blank", "{}"), &state, &control, Utc::now()).status, 404);
+    assert_eq!(handle(&Request::new("DELETE", "/message", ""), &state, &control, Utc::now()).status, 404);
+    assert_eq!(handle(&Request::new("GET", "/departures", ""), &state, &control, Utc::now()).status, 200);
+}
+
+#[test]
+fn test_http_config() {
+    let config: HttpConfig = toml::from_str("").unwrap();
+    assert_eq!(config.listen, "127.0.0.1:8080");
+    assert!(!config.is_unprotected());
+
+    let open: HttpConfig = toml::from_str("listen = \"0.0.0.0:8080\"").unwrap();
+    assert!(open.is_unprotected());
+    let protected: HttpConfig = toml::from_str("listen = \"0.0.0.0:8080\"\ntoken = \"s3cret\"").unwrap();
+    assert!(!protected.is_unprotected());
+}
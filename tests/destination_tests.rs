@@ -0,0 +1,80 @@
+use rusty_bvg::destination::{clean, Pattern, Rule};
+use rusty_bvg::Config;
+
+#[test]
+fn test_default_rules() {
+    let cases = [
+        ("Springpfuhl (Berlin)", "Springpfuhl"),
+        ("S Erkner Bhf", "Erkner"),
+        ("U Berliner Str.", "Berliner Str."),
+        ("S+U Warschauer Str.", "Warschauer Str."),
+        ("Potsdam Hauptbahnhof Bhf", "Potsdam Hauptbahnhof"),
+        ("S Spandau Bhf (Berlin)", "Spandau"),
+        ("Ringbahn S42 ⟲", "Ringbahn S42"),
+        ("Ringbahn S41 ⟳", "Ringbahn S41"),
+        // Only at the end, the rest of the name is kept
+        ("S Bhf Südkreuz Ost", "Bhf Südkreuz Ost"),
+        ("Alt-Mariendorf (Berlin) Nord", "Alt-Mariendorf (Berlin) Nord"),
+        // A capital S without a space is part of the name
+        ("Strausberg Nord", "Strausberg Nord"),
+        ("Uhlandstr.", "Uhlandstr."),
+        ("S", "S"),
+        ("  Hönow  ", "Hönow"),
+    ];
+    for (raw, expected) in cases {
+        assert_eq!(clean(raw, &[], true), expected, "cleaning '{}'", raw);
+    }
+}
+
+#[test]
+fn test_rules_without_defaults() {
+    assert_eq!(clean("S Spandau Bhf (Berlin)", &[], false), "S Spandau Bhf (Berlin)");
+}
+
+#[test]
+fn test_configured_rules() {
+    let alias = Rule::Alias { name: "Flughafen BER Terminal 1-2".to_string(), to: "BER".to_string() };
+    let replace = Rule::Replace { pattern: Pattern::new(r"\bStr\.").unwrap(), with: "Straße".to_string() };
+    let strip = Rule::StripSuffix { suffix: " Nord".to_string() };
+    let rules = [alias, replace, strip];
+
+    let cases = [
+        ("Flughafen BER Terminal 1-2", "BER"),
+        ("S Flughafen BER Terminal 1-2", "Flughafen BER Terminal 1-2"),
+        ("U Berliner Str.", "Berliner Straße"),
+        ("S Strausberg Nord", "Strausberg"),
+        // Nothing left after the rule, the rule is skipped
+        (" Nord", "Nord"),
+    ];
+    for (raw, expected) in cases {
+        assert_eq!(clean(raw, &rules, true), expected, "cleaning '{}'", raw);
+    }
+}
+
+#[test]
+fn test_rules_from_config() {
+    let config = Config::from_toml(
+        r#"
+[fetch]
+default_destinations = false
+
+[[fetch.destinations]]
+type = "alias"
+name = "Flughafen BER Terminal 1-2"
+to = "BER"
+
+[[fetch.destinations]]
+type = "replace"
+pattern = "^S\\+U "
+with = ""
+"#,
+    )
+    .unwrap();
+    let rules = &config.fetch.destinations;
+    assert_eq!(rules.len(), 2);
+    assert_eq!(clean("Flughafen BER Terminal 1-2", rules, false), "BER");
+    assert_eq!(clean("S+U Pankow Bhf", rules, config.fetch.default_destinations), "Pankow Bhf");
+
+    assert!(Config::from_toml("[[fetch.destinations]]\ntype = \"replace\"\npattern = \"(\"\nwith = \"\"").is_err());
+    assert!(Config::from_toml("[[fetch.destinations]]\ntype = \"strip_prefix\"\nsuffix = \"S \"").is_err());
+}
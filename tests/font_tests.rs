@@ -0,0 +1,75 @@
+use rusty_bvg::font::{to_latin1, BdfFont, FontChoice};
+use rusty_bvg::Config;
+use std::path::PathBuf;
+
+// Two glyphs cut down from rpi-rgb-led-matrix/fonts/5x7.bdf, bitmaps omitted
+const SAMPLE_BDF: &str = "\
+STARTFONT 2.1
+FONT -Misc-Fixed-Medium-R-Normal--7-70-75-75-C-50-ISO10646-1
+SIZE 7 75 75
+FONTBOUNDINGBOX 5 7 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 6
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR A
+ENCODING 65
+SWIDTH 480 0
+DWIDTH 5 0
+BBX 5 7 0 -1
+BITMAP
+ENDCHAR
+STARTCHAR odieresis
+ENCODING 246
+SWIDTH 480 0
+DWIDTH 4 0
+BBX 5 7 0 -1
+BITMAP
+ENDCHAR
+ENDFONT
+";
+
+#[test]
+fn test_font_choice_parse() {
+    assert_eq!(FontChoice::parse("4x6"), Ok(FontChoice::Font4x6));
+    assert_eq!(FontChoice::parse("5x7"), Ok(FontChoice::Font5x7));
+    assert_eq!(FontChoice::parse("6x10"), Ok(FontChoice::Font6x10));
+    assert_eq!(
+        FontChoice::parse("/usr/local/share/fonts/6x13.bdf"),
+        Ok(FontChoice::Bdf(PathBuf::from("/usr/local/share/fonts/6x13.bdf")))
+    );
+    assert!(FontChoice::parse("comic-sans").is_err());
+}
+
+#[test]
+fn test_font_from_config() {
+    let config = Config::from_toml("[display]\nfont = \"6x10\"").unwrap();
+    assert_eq!(config.display.font, FontChoice::Font6x10);
+
+    let config = Config::default();
+    assert_eq!(config.display.font, FontChoice::Font4x6);
+}
+
+#[test]
+fn test_bdf_parse() {
+    let font = BdfFont::parse(SAMPLE_BDF).unwrap();
+    assert_eq!(font.metrics.char_width, 5);
+    assert_eq!(font.metrics.height, 7);
+    assert_eq!(font.metrics.baseline, 6);
+    assert_eq!(font.advances.get(&'A'), Some(&5));
+    assert_eq!(font.advances.get(&'ö'), Some(&4));
+}
+
+#[test]
+fn test_bdf_parse_missing_bounding_box() {
+    assert!(BdfFont::parse("STARTFONT 2.1\nENDFONT\n").is_err());
+}
+
+#[test]
+fn test_to_latin1_keeps_umlauts() {
+    assert_eq!(to_latin1("Schöneweide"), "Schöneweide");
+    assert_eq!(to_latin1("Tegeler Weg/Straße"), "Tegeler Weg/Straße");
+    assert_eq!(to_latin1("Görlitzer Bhf – Ost"), "Görlitzer Bhf - Ost");
+    assert_eq!(to_latin1("Ring ⟳"), "Ring ?");
+}
@@ -0,0 +1,102 @@
+use rusty_bvg::font::{BdfFont, FontChoice, FontMetrics, TextMeasure};
+use rusty_bvg::layout::{truncate, wrap};
+use rusty_bvg::Departure;
+use std::collections::HashMap;
+
+fn font_4x6() -> FontMetrics {
+    FontChoice::Font4x6.builtin_metrics().unwrap()
+}
+
+#[test]
+fn test_text_width_counts_chars_not_bytes() {
+    let font = font_4x6();
+    // 'ö' and 'ß' are two bytes each but one glyph
+    assert_eq!(font.text_width("Schöneweide"), 11 * 4);
+    assert_eq!(font.text_width("Straße"), 6 * 4);
+}
+
+#[test]
+fn test_truncate_adds_ellipsis() {
+    let font = font_4x6();
+    assert_eq!(truncate("Erkner", 64, &font), "Erkner");
+    // 32px = 8 chars, 2 of them for ".."
+    assert_eq!(truncate("Strausberg Nord", 32, &font), "Straus..");
+    assert!(font.text_width(&truncate("Strausberg Nord", 32, &font)) <= 32);
+}
+
+#[test]
+fn test_truncate_multibyte() {
+    let font = font_4x6();
+    // Must not panic on a char boundary and must keep whole umlauts
+    assert_eq!(truncate("Görlitzer Bahnhof", 20, &font), "Gör..");
+}
+
+#[test]
+fn test_wrap_by_pixels() {
+    let font = font_4x6();
+    let lines = wrap("U1 Uhlandstr. via Kurfürstendamm", 62, 2, &font);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], "U1 Uhlandstr.");
+    // Second line doesn't fit the rest, ends with an ellipsis
+    assert!(lines[1].ends_with(".."));
+    for line in &lines {
+        assert!(font.text_width(line) <= 62, "'{}' too wide", line);
+    }
+}
+
+#[test]
+fn test_wrap_pads_empty_lines() {
+    let font = font_4x6();
+    assert_eq!(wrap("S5 Erkner", 62, 2, &font), vec!["S5 Erkner".to_string(), String::new()]);
+}
+
+#[test]
+fn test_wrap_wider_font_fits_less() {
+    let font = FontChoice::Font6x10.builtin_metrics().unwrap();
+    let lines = wrap("S7 Potsdam Hbf", 62, 2, &font);
+    assert_eq!(lines, vec!["S7 Potsdam".to_string(), "Hbf".to_string()]);
+}
+
+#[test]
+fn test_proportional_font_measurement() {
+    let mut advances = HashMap::new();
+    advances.insert('i', 2);
+    advances.insert('W', 6);
+    let font = BdfFont {
+        metrics: FontMetrics { char_width: 4, height: 7, baseline: 6 },
+        advances,
+    };
+    assert_eq!(font.text_width("iW"), 8);
+    // Unknown glyphs fall back to the bounding box width
+    assert_eq!(font.text_width("x"), 4);
+}
+
+#[test]
+fn test_departure_format_truncated_multibyte() {
+    let dep = Departure::new("S9", "Schöneweide", 3);
+    let truncated = dep.format_truncated(14);
+    assert_eq!(truncated.chars().count(), 14);
+    assert_eq!(truncated, "S9 Schön 3 min");
+}
+
+#[test]
+fn test_grid_classic_panel() {
+    let grid = rusty_bvg::layout::Grid::new(32, font_4x6());
+    assert_eq!(grid.rows, 3);
+    assert_eq!(grid.baseline(0), 5);
+    assert_eq!(grid.baseline(2), 23);
+    assert_eq!(grid.departures_per_screen(), 1);
+}
+
+#[test]
+fn test_grid_scales_with_canvas() {
+    // 128x64 board fits two departures in 4x6
+    let grid = rusty_bvg::layout::Grid::new(64, font_4x6());
+    assert_eq!(grid.rows, 7);
+    assert_eq!(grid.departures_per_screen(), 2);
+
+    // 6x10 on a 32px panel: one destination row plus the time
+    let grid = rusty_bvg::layout::Grid::new(32, FontChoice::Font6x10.builtin_metrics().unwrap());
+    assert_eq!(grid.rows, 2);
+    assert_eq!(grid.rows_per_departure(), 2);
+}
@@ -0,0 +1,199 @@
+use chrono::{Duration, TimeZone, Utc};
+use rusty_bvg::cache::{Cache, STALE_MAX_AGE};
+use rusty_bvg::line::{is_line_command, render, LineConfig};
+use rusty_bvg::template::{Template, Words};
+use rusty_bvg::destination::Rule;
+use rusty_bvg::urgency::Urgency;
+use rusty_bvg::api::with_urgency;
+use rusty_bvg::{BoardMode, Config, Departure, FetchConfig, StopConfig};
+use std::time::Duration as StdDuration;
+
+fn departures() -> Vec<Departure> {
+    vec![
+        Departure::new("U1", "Uhlandstr.", 3),
+        Departure::new("S5", "Strausberg Nord", 7),
+        Departure::new("M10", "Hauptbahnhof", 9),
+        Departure::new("U3", "Krumme Lanke", 12),
+    ]
+}
+
+#[test]
+fn test_default_line() {
+    assert_eq!(render(&departures(), &LineConfig::default(), &Words::default()), "U1 3' · S5 7' · M10 9'");
+    assert_eq!(render(&[], &LineConfig::default(), &Words::default()), "");
+
+    let config = LineConfig { empty: "–".to_string(), ..LineConfig::default() };
+    assert_eq!(render(&[], &config, &Words::default()), "–");
+}
+
+#[test]
+fn test_line_max_chars() {
+    // Whole departures are dropped, not cut in half
+    let config = LineConfig { max_chars: 16, ..LineConfig::default() };
+    assert_eq!(render(&departures(), &config, &Words::default()), "U1 3' · S5 7'");
+
+    // A single departure that doesn't fit is truncated
+    let config = LineConfig {
+        template: Template::parse("{line} {dest}").unwrap(),
+        max_chars: 8,
+        ..LineConfig::default()
+    };
+    assert_eq!(render(&departures(), &config, &Words::default()), "U1 Uhlan");
+}
+
+#[test]
+fn test_line_config() {
+    let config = Config::from_toml("[line]\ntemplate = \"{line}:{min}\"\ncount = 2").unwrap();
+    assert_eq!(render(&departures(), &config.line, &config.locale.words()), "U1:3 · S5:7");
+    assert!(Config::from_toml("[line]\ncount = 0").is_err());
+}
+
+#[test]
+fn test_cache_freshness() {
+    let fetched = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let stops = vec![StopConfig::new("900120003")];
+    let fetch = FetchConfig::default();
+    let cache = Cache::new(&stops, &fetch, departures(), fetched);
+    let max_age = StdDuration::from_secs(30);
+
+    assert!(cache.is_fresh(&stops, &fetch, fetched + Duration::seconds(30), max_age));
+    assert!(!cache.is_fresh(&stops, &fetch, fetched + Duration::seconds(31), max_age));
+    assert!(!cache.is_fresh(&[StopConfig::new("900100003")], &fetch, fetched, max_age));
+    // Clock went backwards: don't trust it
+    assert!(!cache.is_fresh(&stops, &fetch, fetched - Duration::seconds(5), max_age));
+    // Too old even while the API is down
+    assert!(cache.is_fresh(&stops, &fetch, fetched + Duration::minutes(60), STALE_MAX_AGE));
+    assert!(!cache.is_fresh(&stops, &fetch, fetched + Duration::minutes(61), STALE_MAX_AGE));
+}
+
+#[test]
+fn test_cache_fetch_settings() {
+    let fetched = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let stops = vec![StopConfig::new("900120003")];
+    let fetch = FetchConfig::default();
+    let cache = Cache::new(&stops, &fetch, departures(), fetched);
+    let max_age = StdDuration::from_secs(30);
+
+    // The interval doesn't change the board
+    let slower = FetchConfig { interval: 60, ..fetch.clone() };
+    assert!(cache.is_fresh(&stops, &slower, fetched, max_age));
+
+    let alias = Rule::Alias { name: "Uhlandstr.".to_string(), to: "West".to_string() };
+    for changed in [
+        FetchConfig { mode: BoardMode::Arrivals, ..fetch.clone() },
+        FetchConfig { hide_lines: vec!["U1".to_string()], ..fetch.clone() },
+        FetchConfig { destinations: vec![alias], ..fetch.clone() },
+        FetchConfig { default_destinations: false, ..fetch.clone() },
+        FetchConfig { ringbahn: true, ..fetch.clone() },
+    ] {
+        assert!(!cache.is_fresh(&stops, &changed, fetched, max_age), "{:?}", changed);
+    }
+
+    // Walking times decide urgency and which departures are left, the name is only a label
+    let named = vec![StopConfig { name: Some("Warschauer Str.".to_string()), ..stops[0].clone() }];
+    assert!(cache.is_fresh(&named, &fetch, fetched, max_age));
+    for changed in [
+        StopConfig { walking_time: Some(4), ..stops[0].clone() },
+        StopConfig { leave_now: 5, ..stops[0].clone() },
+    ] {
+        assert!(!cache.is_fresh(&[changed], &fetch, fetched, max_age));
+    }
+}
+
+#[test]
+fn test_cache_counts_down() {
+    let fetched = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let cache = Cache::new(&[StopConfig::new("900120003")], &FetchConfig::default(), departures(), fetched);
+
+    let later: Vec<u32> = cache
+        .departures_at(fetched + Duration::seconds(7 * 60 + 30), &[])
+        .iter()
+        .map(|d| d.minutes)
+        .collect();
+    // The S5 in 7 minutes is due now, not gone
+    assert_eq!(later, vec![0, 2, 5]);
+}
+
+#[test]
+fn test_cache_reclassifies_urgency() {
+    let fetched = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let stop = StopConfig { walking_time: Some(4), ..StopConfig::new("900120003") };
+    let fetched_departures = departures().into_iter().map(|d| d.with_stop("900120003")).collect();
+    let board = with_urgency(fetched_departures, &stop);
+    let cache = Cache::new(std::slice::from_ref(&stop), &FetchConfig::default(), board, fetched);
+
+    // 4 minutes later the S5 is 3 minutes away, too late with a 4 minute walk
+    let later: Vec<(u32, Option<Urgency>)> = cache
+        .departures_at(fetched + Duration::minutes(4), &[stop])
+        .iter()
+        .map(|d| (d.minutes, d.urgency))
+        .collect();
+    assert_eq!(later, vec![(5, Some(Urgency::LeaveNow)), (8, Some(Urgency::Relaxed))]);
+}
+
+#[test]
+fn test_cache_roundtrip() {
+    let path = std::env::temp_dir().join(format!("rusty-bvg-line-test-{}.json", std::process::id()));
+    let cache = Cache::new(&[StopConfig::new("900120003")], &FetchConfig::default(), departures(), Utc::now());
+    cache.save(&path).unwrap();
+    assert_eq!(Cache::load(&path), Some(cache));
+
+    std::fs::write(&path, "garbage").unwrap();
+    assert_eq!(Cache::load(&path), None);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cache_refuses_files_others_can_write() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("rusty-bvg-line-perm-test-{}.json", std::process::id()));
+    let link = dir.join(format!("rusty-bvg-line-link-test-{}.json", std::process::id()));
+    let cache = Cache::new(&[StopConfig::new("900120003")], &FetchConfig::default(), departures(), Utc::now());
+    cache.save(&path).unwrap();
+    assert!(Cache::load(&path).is_some());
+
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o666)).unwrap();
+    assert_eq!(Cache::load(&path), None);
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    // Planted symlinks aren't followed
+    std::os::unix::fs::symlink(&path, &link).unwrap();
+    assert_eq!(Cache::load(&link), None);
+
+    std::fs::remove_file(&link).unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cache_save_ignores_leftover_temp_file() {
+    let path = std::env::temp_dir().join(format!("rusty-bvg-line-leftover-test-{}.json", std::process::id()));
+    // What a crashed run with the same pid would have left behind
+    let leftover = path.with_extension(format!("tmp.{}", std::process::id()));
+    std::fs::write(&leftover, "partial").unwrap();
+
+    let cache = Cache::new(&[StopConfig::new("900120003")], &FetchConfig::default(), departures(), Utc::now());
+    cache.save(&path).unwrap();
+    cache.save(&path).unwrap();
+    assert_eq!(Cache::load(&path), Some(cache));
+
+    std::fs::remove_file(&leftover).unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_line_command_after_options() {
+    let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert!(is_line_command(args(&["line"])));
+    assert!(is_line_command(args(&["line", "--config", "/etc/bvg.toml"])));
+    assert!(is_line_command(args(&["--config", "/etc/bvg.toml", "line"])));
+    assert!(is_line_command(args(&["--config=/etc/bvg.toml", "line"])));
+    assert!(is_line_command(args(&["--output", "json", "line"])));
+
+    assert!(!is_line_command(args(&[])));
+    assert!(!is_line_command(args(&["--output", "json"])));
+    // A config file that happens to be called "line"
+    assert!(!is_line_command(args(&["--config", "line"])));
+    assert!(!is_line_command(args(&["--tui"])));
+}
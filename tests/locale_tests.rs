@@ -0,0 +1,44 @@
+use chrono::{TimeZone, Utc};
+use rusty_bvg::locale::Locale;
+use rusty_bvg::output::{render, OutputFormat};
+use rusty_bvg::template::{default_template, Template};
+use rusty_bvg::{Config, Departure};
+
+#[test]
+fn test_departure_format_in_german() {
+    assert_eq!(Departure::new("S3", "Erkner", 5).format_in(Locale::De), "S3 Erkner 5 Min.");
+    assert_eq!(Departure::new("S3", "Erkner", 0).format_in(Locale::De), "S3 Erkner sofort");
+    assert_eq!(Departure::new("S3", "Erkner", 0).format_in(Locale::En), "S3 Erkner now");
+}
+
+#[test]
+fn test_cancelled_departure() {
+    let cancelled = Departure::new("U1", "Uhlandstr.", 4).with_cancelled(true);
+    assert_eq!(cancelled.format_in(Locale::De), "U1 Uhlandstr. fällt aus");
+    assert_eq!(cancelled.format(), "U1 Uhlandstr. cancelled");
+
+    let template = Template::parse("{line} {min}").unwrap();
+    assert_eq!(cancelled.format_with(&template, &Locale::De.words()), "U1 fällt aus");
+}
+
+#[test]
+fn test_german_table() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let departures = vec![
+        Departure::new("U1", "Uhlandstr.", 3),
+        Departure::new("M10", "Hauptbahnhof", 12).with_cancelled(true),
+    ];
+    assert_eq!(
+        render(OutputFormat::Table, &departures, now, &default_template(), Locale::De).unwrap(),
+        "LINIE  ZIEL               MIN.\nU1     Uhlandstr.            3\nM10    Hauptbahnhof  fällt aus\n\n"
+    );
+}
+
+#[test]
+fn test_locale_config() {
+    assert_eq!(Config::default().locale, Locale::En);
+    let config = Config::from_toml("locale = \"de\"").unwrap();
+    assert_eq!(config.locale, Locale::De);
+    assert_eq!(config.locale.strings().no_disruptions, "Keine Störungen");
+    assert!(Config::from_toml("locale = \"fr\"").is_err());
+}
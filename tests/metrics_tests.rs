@@ -0,0 +1,63 @@
+use chrono::{Duration, TimeZone, Utc};
+use rusty_bvg::control::BoardState;
+use rusty_bvg::api::{ErrorKind, FetchError};
+use rusty_bvg::metrics::{resident_memory_bytes, Histogram, Metrics};
+use rusty_bvg::Departure;
+use std::error::Error;
+use std::time::Duration as StdDuration;
+
+#[test]
+fn test_histogram_buckets_are_cumulative() {
+    static BOUNDS: [f64; 2] = [0.5, 1.0];
+    let mut histogram = Histogram::new(&BOUNDS);
+    histogram.observe(0.2);
+    histogram.observe(0.7);
+    histogram.observe(3.0);
+    assert_eq!(histogram.count(), 3);
+
+    let metrics = Metrics { fetch_duration: histogram, ..Metrics::default() };
+    let text = metrics.render(None, Utc::now());
+    assert!(text.contains("rusty_bvg_fetch_duration_seconds_bucket{le=\"0.5\"} 1\n"));
+    assert!(text.contains("rusty_bvg_fetch_duration_seconds_bucket{le=\"1\"} 2\n"));
+    assert!(text.contains("rusty_bvg_fetch_duration_seconds_bucket{le=\"+Inf\"} 3\n"));
+    assert!(text.contains("rusty_bvg_fetch_duration_seconds_count 3\n"));
+}
+
+#[test]
+fn test_error_kinds() {
+    let http: Box<dyn Error> = FetchError::new(ErrorKind::Http, "HTTP error: timeout").into();
+    assert_eq!(FetchError::kind_of(http.as_ref()), ErrorKind::Http);
+    assert_eq!(http.to_string(), "HTTP error: timeout");
+    // Only the kind counts, not how the message starts
+    let parse: Box<dyn Error> = FetchError::new(ErrorKind::Parse, "HTTP error in the body").into();
+    assert_eq!(FetchError::kind_of(parse.as_ref()), ErrorKind::Parse);
+    let other: Box<dyn Error> = "Unsupported stop_id: x".into();
+    assert_eq!(FetchError::kind_of(other.as_ref()), ErrorKind::Other);
+}
+
+#[test]
+fn test_fetches_are_counted() {
+    let start = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let mut state = BoardState::default();
+
+    let ok: Result<Vec<Departure>, Box<dyn Error>> = Ok(vec![Departure::new("U1", "Uhlandstr.", 3); 4]);
+    state.record_fetch(&ok, start, StdDuration::from_millis(300));
+    let failed: Result<Vec<Departure>, Box<dyn Error>> = Err(FetchError::new(ErrorKind::Parse, "JSON parse error: eof").into());
+    state.record_fetch(&failed, start, StdDuration::from_millis(200));
+    state.record_render(StdDuration::from_millis(3));
+
+    let text = state.metrics.render(state.last_success, start + Duration::seconds(42));
+    assert!(text.contains("rusty_bvg_fetch_success_total 1\n"));
+    assert!(text.contains("rusty_bvg_fetch_errors_total{kind=\"parse\"} 1\n"));
+    assert!(text.contains("rusty_bvg_fetch_errors_total{kind=\"http\"} 0\n"));
+    assert!(text.contains("rusty_bvg_departures 4\n"));
+    assert!(text.contains("rusty_bvg_data_age_seconds 42\n"));
+    assert!(text.contains("rusty_bvg_render_duration_seconds_count 1\n"));
+}
+
+#[test]
+fn test_resident_memory() {
+    if cfg!(target_os = "linux") {
+        assert!(resident_memory_bytes().unwrap() > 0);
+    }
+}
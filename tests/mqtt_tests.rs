@@ -0,0 +1,109 @@
+use chrono::{TimeZone, Utc};
+use rusty_bvg::mqtt::{BoardPublisher, Message, MqttConfig};
+use rusty_bvg::{Config, Departure, StopConfig};
+
+fn config() -> MqttConfig {
+    Config::from_toml("[mqtt]\nhost = \"localhost\"").unwrap().mqtt.unwrap()
+}
+
+fn stop() -> StopConfig {
+    StopConfig { name: Some("Warschauer Str.".to_string()), ..StopConfig::new("900120003") }
+}
+
+fn topics(messages: &[Message]) -> Vec<&str> {
+    messages.iter().map(|m| m.topic.as_str()).collect()
+}
+
+#[test]
+fn test_mqtt_config_defaults() {
+    let config = config();
+    assert_eq!(config.port, 1883);
+    assert_eq!(config.topic_prefix, "rusty-bvg");
+    assert!(config.discovery);
+    assert!(config.commands);
+    assert_eq!(BoardPublisher::new(&config).command_topic(), "rusty-bvg/command");
+    assert!(Config::from_toml("[mqtt]\nport = 1883").is_err());
+}
+
+#[test]
+fn test_board_and_line_sensors() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let mut publisher = BoardPublisher::new(&config());
+    let departures = vec![
+        Departure::new("U1", "Uhlandstr.", 2).with_stop("900120003"),
+        Departure::new("U1", "Uhlandstr.", 7).with_stop("900120003"),
+        Departure::new("M10", "Hauptbahnhof", 4).with_stop("900120003"),
+    ];
+
+    let messages = publisher.messages(&[stop()], &departures, now);
+    assert_eq!(
+        topics(&messages),
+        vec![
+            "rusty-bvg/900120003/departures",
+            "homeassistant/sensor/rusty-bvg/900120003_U1/config",
+            "rusty-bvg/900120003/lines/U1",
+            "homeassistant/sensor/rusty-bvg/900120003_M10/config",
+            "rusty-bvg/900120003/lines/M10",
+        ]
+    );
+    assert!(messages.iter().all(|m| m.retain));
+
+    let board: serde_json::Value = serde_json::from_str(&messages[0].payload).unwrap();
+    assert_eq!(board["name"], "Warschauer Str.");
+    assert_eq!(board["departures"].as_array().unwrap().len(), 3);
+    assert_eq!(board["departures"][0]["stop"], "900120003");
+    assert!(board["departures"][0].get("color").is_none());
+
+    // The next U1 is the one in 2 minutes
+    let line: serde_json::Value = serde_json::from_str(&messages[2].payload).unwrap();
+    assert_eq!(line["minutes"], 2);
+
+    let discovery: serde_json::Value = serde_json::from_str(&messages[1].payload).unwrap();
+    assert_eq!(discovery["state_topic"], "rusty-bvg/900120003/lines/U1");
+    assert_eq!(discovery["unit_of_measurement"], "min");
+    assert_eq!(discovery["availability_topic"], "rusty-bvg/status");
+}
+
+#[test]
+fn test_vanished_line_is_cleared_and_announced_once() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let mut publisher = BoardPublisher::new(&config());
+    let first = vec![
+        Departure::new("U1", "Uhlandstr.", 2).with_stop("900120003"),
+        Departure::new("M10", "Hauptbahnhof", 4).with_stop("900120003"),
+    ];
+    for message in publisher.messages(&[stop()], &first, now) {
+        publisher.published(&message);
+    }
+
+    let second = vec![Departure::new("M10", "Hauptbahnhof", 3).with_stop("900120003")];
+    let messages = publisher.messages(&[stop()], &second, now);
+    assert_eq!(
+        topics(&messages),
+        vec![
+            "rusty-bvg/900120003/departures",
+            "rusty-bvg/900120003/lines/M10",
+            "rusty-bvg/900120003/lines/U1",
+        ]
+    );
+    assert_eq!(messages[2].payload, r#"{"minutes":null}"#);
+}
+
+#[test]
+fn test_discovery_repeated_until_published() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let mut publisher = BoardPublisher::new(&config());
+    let departures = vec![Departure::new("U1", "Uhlandstr.", 2).with_stop("900120003")];
+    let discovery = "homeassistant/sensor/rusty-bvg/900120003_U1/config";
+
+    // Queue full: the discovery message was dropped
+    let dropped = publisher.messages(&[stop()], &departures, now);
+    assert!(topics(&dropped).contains(&discovery));
+
+    let messages = publisher.messages(&[stop()], &departures, now);
+    assert!(topics(&messages).contains(&discovery));
+    for message in &messages {
+        publisher.published(message);
+    }
+    assert!(!topics(&publisher.messages(&[stop()], &departures, now)).contains(&discovery));
+}
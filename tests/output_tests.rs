@@ -0,0 +1,83 @@
+use chrono::{TimeZone, Utc};
+use rusty_bvg::locale::Locale;
+use rusty_bvg::output::{render, OutputFormat};
+use rusty_bvg::template::default_template;
+use rusty_bvg::urgency::Urgency;
+use rusty_bvg::Departure;
+
+fn departures() -> Vec<Departure> {
+    vec![
+        Departure::new("U1", "Uhlandstr.", 3)
+            .with_stop("900120003")
+            .with_urgency(Some(Urgency::LeaveNow)),
+        Departure::new("M10", "Hauptbahnhof", 12)
+            .with_stop("900120003")
+            .with_warnings(vec!["Bauarbeiten".to_string()]),
+    ]
+}
+
+#[test]
+fn test_parse_format() {
+    assert_eq!("ndjson".parse(), Ok(OutputFormat::Ndjson));
+    assert_eq!("table".parse(), Ok(OutputFormat::Table));
+    assert!("yaml".parse::<OutputFormat>().is_err());
+    assert_eq!(OutputFormat::default(), OutputFormat::Log);
+}
+
+#[test]
+fn test_json_board() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let out = render(OutputFormat::Json, &departures(), now, &default_template(), Locale::En).unwrap();
+    assert_eq!(out.lines().count(), 1);
+
+    let board: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(board["updated"], "2025-03-10T08:00:00+00:00");
+    assert_eq!(
+        board["departures"][0],
+        serde_json::json!({
+            "stop": "900120003",
+            "line": "U1",
+            "destination": "Uhlandstr.",
+            "minutes": 3,
+            "urgency": "leave_now",
+            "warnings": [],
+            "delay": null,
+            "platform": null,
+            "planned": null,
+            "cancelled": false,
+            "ring": null,
+        })
+    );
+    assert_eq!(board["departures"][1]["warnings"][0], "Bauarbeiten");
+
+    // No departures is still a board
+    let empty = render(OutputFormat::Json, &[], now, &default_template(), Locale::En).unwrap();
+    assert_eq!(empty, "{\"updated\":\"2025-03-10T08:00:00+00:00\",\"departures\":[]}\n");
+}
+
+#[test]
+fn test_ndjson_line_per_departure() {
+    let now = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+    let out = render(OutputFormat::Ndjson, &departures(), now, &default_template(), Locale::En).unwrap();
+    let lines: Vec<serde_json::Value> = out.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["line"], "M10");
+    assert_eq!(lines[1]["updated"], "2025-03-10T08:00:00+00:00");
+    assert!(lines[1]["urgency"].is_null());
+}
+
+#[test]
+fn test_table_and_plain() {
+    let now = Utc::now();
+    let departures = vec![Departure::new("U1", "Uhlandstr.", 3), Departure::new("M10", "Hauptbahnhof", 12)];
+    assert_eq!(
+        render(OutputFormat::Table, &departures, now, &default_template(), Locale::En).unwrap(),
+        "LINE  DESTINATION   MIN\nU1    Uhlandstr.      3\nM10   Hauptbahnhof   12\n\n"
+    );
+    assert_eq!(
+        render(OutputFormat::Plain, &departures, now, &default_template(), Locale::En).unwrap(),
+        "U1 Uhlandstr. 3 min\nM10 Hauptbahnhof 12 min\n"
+    );
+    assert_eq!(render(OutputFormat::Table, &[], now, &default_template(), Locale::En).unwrap(), "");
+    assert_eq!(render(OutputFormat::Log, &departures, now, &default_template(), Locale::En), None);
+}
@@ -0,0 +1,200 @@
+use rusty_bvg::pages::{Condition, Page, PageContext, PageKind, PageScheduler, Priority};
+use rusty_bvg::{collect_warnings, Config, Departure};
+use std::time::{Duration, Instant};
+
+const NO_WARNINGS: PageContext = PageContext { has_departures: true, has_warnings: false };
+const WARNINGS: PageContext = PageContext { has_departures: true, has_warnings: true };
+
+fn rotation() -> Vec<Page> {
+    Config::from_toml(
+        r#"
+        [[display.pages]]
+        type = "board"
+        duration = 20
+
+        [[display.pages]]
+        type = "disruptions"
+        duration = 5
+        when = "has_warnings"
+
+        [[display.pages]]
+        type = "clock"
+        duration = 5
+        "#,
+    )
+    .unwrap()
+    .display
+    .pages
+}
+
+#[test]
+fn test_default_rotation_is_single_departures() {
+    let scheduler = PageScheduler::new(Vec::new(), Instant::now());
+    assert_eq!(scheduler.current().kind, PageKind::Departure);
+    assert_eq!(scheduler.current().duration, 10);
+}
+
+#[test]
+fn test_pages_rotate_after_duration() {
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(rotation(), start);
+
+    assert!(!scheduler.tick(start + Duration::from_secs(19), &WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+
+    assert!(scheduler.tick(start + Duration::from_secs(20), &WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Disruptions);
+
+    assert!(scheduler.tick(start + Duration::from_secs(25), &WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Clock);
+
+    assert!(scheduler.tick(start + Duration::from_secs(30), &WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+}
+
+#[test]
+fn test_conditional_page_is_skipped() {
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(rotation(), start);
+
+    assert!(scheduler.tick(start + Duration::from_secs(20), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Clock);
+}
+
+#[test]
+fn test_page_left_when_condition_stops_holding() {
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(rotation(), start);
+    scheduler.tick(start + Duration::from_secs(20), &WARNINGS);
+    assert_eq!(scheduler.current().kind, PageKind::Disruptions);
+
+    // Warnings gone before the page's time is up
+    assert!(scheduler.tick(start + Duration::from_secs(21), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Clock);
+}
+
+#[test]
+fn test_nothing_matches_keeps_current_page() {
+    let mut page = Page::new(PageKind::Disruptions, 5);
+    page.when = Condition::HasWarnings;
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(vec![page], start);
+
+    scheduler.tick(start + Duration::from_secs(6), &NO_WARNINGS);
+    assert_eq!(scheduler.current().kind, PageKind::Disruptions);
+    // Same page again, nothing to redraw
+    assert!(!scheduler.tick(start + Duration::from_secs(7), &NO_WARNINGS));
+    assert!(!scheduler.tick(start + Duration::from_secs(8), &NO_WARNINGS));
+}
+
+#[test]
+fn test_interrupt_shows_page_then_resumes() {
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(rotation(), start);
+
+    let mut message = Page::new(PageKind::Text, 30);
+    message.text = Some("Kuchen in der Küche".to_string());
+    scheduler.interrupt(message, start + Duration::from_secs(5));
+    assert_eq!(scheduler.current().kind, PageKind::Text);
+
+    // The board page would have expired at 20s, the message still runs
+    assert!(!scheduler.tick(start + Duration::from_secs(25), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Text);
+
+    assert!(scheduler.tick(start + Duration::from_secs(35), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+}
+
+fn message(text: &str, duration: u64) -> Page {
+    let mut page = Page::new(PageKind::Text, duration);
+    page.text = Some(text.to_string());
+    page
+}
+
+fn text(scheduler: &PageScheduler) -> Option<&str> {
+    scheduler.current().text.as_deref()
+}
+
+#[test]
+fn test_announcement_joins_rotation_until_ttl() {
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(rotation(), start);
+    scheduler.announce(message("Team lunch 12:30", 5), 60, Priority::Low, start);
+
+    // Low priority waits for its turn: board, clock, then the message
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+    assert!(scheduler.tick(start + Duration::from_secs(20), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Clock);
+    assert!(scheduler.tick(start + Duration::from_secs(25), &NO_WARNINGS));
+    assert_eq!(text(&scheduler), Some("Team lunch 12:30"));
+    assert!(scheduler.tick(start + Duration::from_secs(30), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+
+    // Second round: 50..55s
+    assert!(scheduler.tick(start + Duration::from_secs(50), &NO_WARNINGS));
+    assert!(scheduler.tick(start + Duration::from_secs(55), &NO_WARNINGS));
+    assert_eq!(text(&scheduler), Some("Team lunch 12:30"));
+
+    // TTL over: gone from the rotation
+    assert!(scheduler.tick(start + Duration::from_secs(60), &NO_WARNINGS));
+    assert!(scheduler.tick(start + Duration::from_secs(80), &NO_WARNINGS));
+    assert!(scheduler.tick(start + Duration::from_secs(85), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+}
+
+#[test]
+fn test_expired_announcement_leaves_screen() {
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(rotation(), start);
+    scheduler.announce(message("Kuchen", 30), 40, Priority::Normal, start);
+    assert_eq!(text(&scheduler), Some("Kuchen"));
+
+    // Shown right away, then in the rotation after the clock
+    assert!(scheduler.tick(start + Duration::from_secs(30), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+    assert!(scheduler.tick(start + Duration::from_secs(50), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Clock);
+    assert!(scheduler.tick(start + Duration::from_secs(55), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+}
+
+#[test]
+fn test_high_priority_is_not_interrupted() {
+    let start = Instant::now();
+    let mut scheduler = PageScheduler::new(rotation(), start);
+    scheduler.announce(message("Fire drill", 5), 120, Priority::High, start);
+
+    scheduler.announce(message("Kuchen", 30), 60, Priority::Normal, start + Duration::from_secs(10));
+    scheduler.interrupt(Page::new(PageKind::Clock, 30), start + Duration::from_secs(10));
+    assert!(!scheduler.tick(start + Duration::from_secs(100), &NO_WARNINGS));
+    assert_eq!(text(&scheduler), Some("Fire drill"));
+
+    // Clearing takes it off the screen, the rotation continues
+    scheduler.clear_messages(start + Duration::from_secs(110));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+    assert!(scheduler.tick(start + Duration::from_secs(130), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Clock);
+    assert!(scheduler.tick(start + Duration::from_secs(135), &NO_WARNINGS));
+    assert_eq!(scheduler.current().kind, PageKind::Board);
+}
+
+#[test]
+fn test_page_validation() {
+    assert!(Config::from_toml("[[display.pages]]\ntype = \"text\"").is_err());
+    assert!(Config::from_toml("[[display.pages]]\ntype = \"clock\"\nduration = 0").is_err());
+    assert!(Config::from_toml("[[display.pages]]\ntype = \"weather\"").is_err());
+
+    let config = Config::from_toml("[[display.pages]]\ntype = \"text\"\ntext = \"Team lunch 12:30\"").unwrap();
+    assert_eq!(config.display.pages[0].text.as_deref(), Some("Team lunch 12:30"));
+}
+
+#[test]
+fn test_collect_warnings_deduplicates() {
+    let departures = vec![
+        Departure::new("U1", "Uhlandstr.", 2).with_warnings(vec!["U1: construction work".to_string()]),
+        Departure::new("U1", "Uhlandstr.", 7).with_warnings(vec!["U1: construction work".to_string()]),
+        Departure::new("S5", "Erkner", 4).with_warnings(vec!["S5: signal failure".to_string()]),
+        Departure::new("M10", "Hbf", 5),
+    ];
+    assert_eq!(collect_warnings(&departures), vec!["U1: construction work", "S5: signal failure"]);
+}
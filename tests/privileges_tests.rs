@@ -0,0 +1,29 @@
+use rusty_bvg::privileges::{drop_privileges, lookup_group, lookup_user, PrivilegesConfig};
+use rusty_bvg::Config;
+
+#[test]
+fn test_lookup() {
+    assert_eq!(lookup_user("root"), Ok((0, 0)));
+    assert_eq!(lookup_group("root"), Ok(0));
+    assert!(lookup_user("no-such-user-rusty-bvg").is_err());
+    assert!(lookup_group("no-such-group-rusty-bvg").is_err());
+    assert!(lookup_user("bad\0name").is_err());
+}
+
+#[test]
+fn test_privileges_config() {
+    assert_eq!(Config::default().privileges, PrivilegesConfig::default());
+    // Never stay root by accident
+    assert_eq!(PrivilegesConfig::default().user.as_deref(), Some("daemon"));
+
+    let config = Config::from_toml("[privileges]\nuser = \"daemon\"\ngroup = \"gpio\"").unwrap();
+    assert_eq!(config.privileges.user.as_deref(), Some("daemon"));
+    assert_eq!(config.privileges.group.as_deref(), Some("gpio"));
+}
+
+#[test]
+fn test_no_user_keeps_privileges() {
+    // No user or root: nothing happens, whoever runs the tests
+    assert!(drop_privileges(&PrivilegesConfig { user: None, group: None }).is_ok());
+    assert!(drop_privileges(&PrivilegesConfig { user: Some("root".to_string()), group: None }).is_ok());
+}
@@ -0,0 +1,59 @@
+use rusty_bvg::config::ConfigWatcher;
+use rusty_bvg::{Config, DisplayConfig};
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn test_watcher_notices_changes_once() {
+    let path = std::env::temp_dir().join(format!("rusty-bvg-watch-{}.toml", std::process::id()));
+    std::fs::write(&path, "[fetch]\ninterval = 30\n").unwrap();
+
+    let mut watcher = ConfigWatcher::new(Some(path.clone()));
+    assert!(!watcher.changed());
+
+    // Set the mtime explicitly, the file system may only have second resolution
+    std::fs::write(&path, "[fetch]\ninterval = 60\n").unwrap();
+    let later = SystemTime::now() + Duration::from_secs(5);
+    File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+
+    // A vanished file is not a change, until it is back
+    std::fs::remove_file(&path).unwrap();
+    assert!(!watcher.changed());
+    assert!(!watcher.changed());
+    std::fs::write(&path, "[fetch]\ninterval = 60\n").unwrap();
+    File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_watcher_without_file() {
+    assert!(!ConfigWatcher::new(None).changed());
+}
+
+#[test]
+fn test_reload_keeps_panel_settings() {
+    let running = DisplayConfig::default();
+
+    let mut layout_only = Config::from_toml("[display]\nfont = \"6x10\"\nbrightness = 50").unwrap().display;
+    assert!(!layout_only.adopt_panel(&running));
+
+    let mut new_panel = Config::from_toml("[display]\nchain_length = 2\nbrightness = 50").unwrap().display;
+    assert!(new_panel.adopt_panel(&running));
+    assert_eq!(new_panel.chain_length, 1);
+    // Everything else is taken from the new config
+    assert_eq!(new_panel.brightness, 50);
+}
+
+#[test]
+fn test_fetch_config() {
+    let config = Config::from_toml("[fetch]\ninterval = 30\nhide_lines = [\"M10\"]").unwrap();
+    assert_eq!(config.fetch.interval, 30);
+    assert_eq!(config.fetch.hide_lines, vec!["M10".to_string()]);
+    assert_eq!(Config::default().fetch.interval, 20);
+
+    assert!(Config::from_toml("[fetch]\ninterval = 1").is_err());
+}
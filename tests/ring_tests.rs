@@ -0,0 +1,55 @@
+use rusty_bvg::ring::RingDirection;
+use rusty_bvg::{Config, Departure};
+
+#[test]
+fn test_direction_for_line() {
+    assert_eq!(RingDirection::for_line("S41"), Some(RingDirection::Clockwise));
+    assert_eq!(RingDirection::for_line("S42"), Some(RingDirection::CounterClockwise));
+    assert_eq!(RingDirection::for_line("S45"), None);
+    assert_eq!(RingDirection::for_line("U4"), None);
+}
+
+#[test]
+fn test_via_next_interchange() {
+    use RingDirection::{Clockwise, CounterClockwise};
+    let cases = [
+        ("S Schöneberg (Berlin)", Clockwise, Some("Westkreuz")),
+        ("S Schöneberg (Berlin)", CounterClockwise, Some("Südkreuz")),
+        ("S+U Frankfurter Allee (Berlin)", Clockwise, Some("Ostkreuz")),
+        ("S+U Frankfurter Allee (Berlin)", CounterClockwise, Some("Gesundbrunnen")),
+        // From an interchange the next one is shown, not the stop itself
+        ("S Ostkreuz Bhf (Berlin)", Clockwise, Some("Südkreuz")),
+        ("S Ostkreuz Bhf (Berlin)", CounterClockwise, Some("Gesundbrunnen")),
+        // Past the end of the list, round the Ring
+        ("S+U Tempelhof (Berlin)", Clockwise, Some("Südkreuz")),
+        ("S Südkreuz Bhf (Berlin)", CounterClockwise, Some("Ostkreuz")),
+        ("S Greifswalder Straße (Berlin)", Clockwise, Some("Ostkreuz")),
+        ("S+U Warschauer Str. (Berlin)", Clockwise, None),
+    ];
+    for (stop, ring, expected) in cases {
+        assert_eq!(ring.via(stop), expected, "{:?} from '{}'", ring, stop);
+    }
+}
+
+#[test]
+fn test_glyphs_are_mirrored() {
+    let clockwise = RingDirection::Clockwise.glyph();
+    let counter = RingDirection::CounterClockwise.glyph();
+    for (a, b) in clockwise.iter().zip(counter) {
+        assert_eq!(a.chars().rev().collect::<String>(), *b);
+    }
+}
+
+#[test]
+fn test_ring_departure_text() {
+    let departure = Departure::new("S41", "Gesundbrunnen", 4).with_ring(Some(RingDirection::Clockwise));
+    assert_eq!(departure.destination_label(), "⟳ Gesundbrunnen");
+    assert_eq!(departure.format(), "S41 ⟳ Gesundbrunnen 4 min");
+    assert_eq!(departure.format_truncated(13), "S41 ⟳ G 4 min");
+}
+
+#[test]
+fn test_ringbahn_option() {
+    assert!(!Config::default().fetch.ringbahn);
+    assert!(Config::from_toml("[fetch]\nringbahn = true").unwrap().fetch.ringbahn);
+}
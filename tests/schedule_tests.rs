@@ -0,0 +1,133 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Timelike};
+use chrono_tz::Tz;
+use rusty_bvg::schedule::{sun_times, DisplayLevel, Schedule, TIMEZONE};
+use rusty_bvg::control::BoardState;
+use rusty_bvg::{Config, Departure};
+use std::error::Error;
+use std::time::Duration;
+
+fn berlin(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Tz> {
+    TIMEZONE.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+fn schedule(toml: &str) -> Schedule {
+    Config::from_toml(toml).unwrap().display.schedule
+}
+
+#[test]
+fn test_empty_schedule_uses_default() {
+    let schedule = Schedule::default();
+    assert!(schedule.is_empty());
+    assert_eq!(schedule.level_at(berlin(2025, 1, 1, 3, 0), 80, false), DisplayLevel::On(80));
+}
+
+#[test]
+fn test_brightness_steps_wrap_midnight() {
+    let schedule = schedule(
+        r#"
+        [display.schedule]
+        steps = [
+            { from = "07:00", brightness = 100 },
+            { from = "21:00", brightness = 30 },
+        ]
+        "#,
+    );
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 12, 0), 100, true), DisplayLevel::On(100));
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 22, 0), 100, true), DisplayLevel::On(30));
+    // Before the first step of the day the last step of yesterday applies
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 5, 0), 100, true), DisplayLevel::On(30));
+}
+
+#[test]
+fn test_night_mode_blank_and_wake() {
+    let schedule = schedule(
+        r#"
+        [display.schedule.night]
+        start = "23:00"
+        end = "06:00"
+        action = "blank"
+        wake_after = "05:00"
+        "#,
+    );
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 23, 30), 100, true), DisplayLevel::Blank);
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 2, 0), 100, true), DisplayLevel::Blank);
+    // After wake_after the first departure switches the panel back on
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 5, 10), 100, false), DisplayLevel::Blank);
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 5, 10), 100, true), DisplayLevel::On(100));
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 6, 0), 100, false), DisplayLevel::On(100));
+}
+
+#[test]
+fn test_empty_fetch_keeps_night_blank() {
+    let schedule = schedule(
+        r#"
+        [display.schedule.night]
+        start = "23:00"
+        end = "06:00"
+        action = "blank"
+        wake_after = "00:30"
+        "#,
+    );
+    let mut state = BoardState::default();
+    let evening: Result<Vec<Departure>, Box<dyn Error>> = Ok(vec![Departure::new("U1", "Uhlandstr.", 8)]);
+    state.record_fetch(&evening, berlin(2025, 3, 10, 0, 20).to_utc(), Duration::from_millis(300));
+    // The last train is gone, the next fetch finds nothing
+    let empty: Result<Vec<Departure>, Box<dyn Error>> = Ok(Vec::new());
+    state.record_fetch(&empty, berlin(2025, 3, 10, 0, 40).to_utc(), Duration::from_millis(300));
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 0, 40), 100, state.has_departures()), DisplayLevel::Blank);
+
+    // A failed fetch doesn't bring the old list back
+    let failed: Result<Vec<Departure>, Box<dyn Error>> = Err("HTTP error: timeout".into());
+    state.record_fetch(&failed, berlin(2025, 3, 10, 1, 0).to_utc(), Duration::from_millis(300));
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 1, 0), 100, state.has_departures()), DisplayLevel::Blank);
+}
+
+#[test]
+fn test_night_mode_dim() {
+    let schedule = schedule(
+        r#"
+        [display.schedule.night]
+        start = "22:00"
+        end = "07:00"
+        action = "dim"
+        brightness = 15
+        "#,
+    );
+    assert_eq!(schedule.level_at(berlin(2025, 3, 10, 22, 0), 100, true), DisplayLevel::On(15));
+}
+
+#[test]
+fn test_sun_schedule_dims_after_sunset() {
+    let schedule = schedule(
+        r#"
+        [display.schedule.sun]
+        night_brightness = 20
+        "#,
+    );
+    // Midsummer in Berlin: light at 21:00 CEST is gone by 22:30
+    assert_eq!(schedule.level_at(berlin(2025, 6, 21, 21, 0), 100, true), DisplayLevel::On(100));
+    assert_eq!(schedule.level_at(berlin(2025, 6, 21, 22, 30), 100, true), DisplayLevel::On(20));
+    // Midwinter: already dark at 17:00
+    assert_eq!(schedule.level_at(berlin(2025, 12, 21, 17, 0), 100, true), DisplayLevel::On(20));
+}
+
+#[test]
+fn test_sun_times_berlin() {
+    let date = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+    let (sunrise, sunset) = sun_times(date, 52.52, 13.405).unwrap();
+    let sunrise = sunrise.with_timezone(&TIMEZONE);
+    let sunset = sunset.with_timezone(&TIMEZONE);
+    // Published times: 04:43 and 21:33 CEST
+    assert_eq!((sunrise.hour(), sunrise.minute() / 10), (4, 4));
+    assert_eq!((sunset.hour(), sunset.minute() / 10), (21, 3));
+}
+
+#[test]
+fn test_schedule_validation() {
+    assert!(Config::from_toml("[display.schedule]\nsteps = [{ from = \"25:00\", brightness = 10 }]").is_err());
+    assert!(Config::from_toml("[display.schedule]\nsteps = [{ from = \"07:00\", brightness = 0 }]").is_err());
+    assert!(Config::from_toml(
+        "[display.schedule.night]\nstart = \"23:00\"\nend = \"06:00\"\naction = \"blank\"\nwake_after = \"12:00\""
+    )
+    .is_err());
+}
@@ -0,0 +1,23 @@
+use rusty_bvg::signals::Signals;
+use signal_hook::consts::{SIGHUP, SIGTERM};
+use signal_hook::low_level::raise;
+use std::time::{Duration, Instant};
+
+// One test: signals are process-wide, parallel tests would see each other's
+#[test]
+fn test_signal_flags() {
+    let signals = Signals::register().unwrap();
+    assert!(!signals.take_reload());
+    assert!(!signals.shutdown_requested());
+
+    raise(SIGHUP).unwrap();
+    // A pending reload cuts the sleep short
+    let start = Instant::now();
+    signals.sleep(Duration::from_secs(5));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(signals.take_reload());
+    assert!(!signals.take_reload());
+
+    raise(SIGTERM).unwrap();
+    assert!(signals.shutdown_requested());
+}
@@ -0,0 +1,89 @@
+use chrono::{Duration, TimeZone, Utc};
+use rusty_bvg::layout::Grid;
+use rusty_bvg::schedule::TIMEZONE;
+use rusty_bvg::status::{Freshness, StatusBarConfig, StatusLine, StatusPosition};
+use rusty_bvg::{Config, StopConfig};
+
+#[test]
+fn test_freshness_thresholds() {
+    let now = Utc::now();
+    assert_eq!(Freshness::from_last_success(now, Some(now - Duration::seconds(20)), 20), Freshness::Fresh);
+    assert_eq!(Freshness::from_last_success(now, Some(now - Duration::minutes(3)), 20), Freshness::Stale);
+    assert_eq!(Freshness::from_last_success(now, Some(now - Duration::minutes(10)), 20), Freshness::Offline);
+    assert_eq!(Freshness::from_last_success(now, None, 20), Freshness::Offline);
+}
+
+#[test]
+fn test_freshness_follows_fetch_interval() {
+    let now = Utc::now();
+    // Every 5 minutes: just before the next fetch is still fresh
+    assert_eq!(Freshness::from_last_success(now, Some(now - Duration::minutes(5)), 300), Freshness::Fresh);
+    assert_eq!(Freshness::from_last_success(now, Some(now - Duration::minutes(14)), 300), Freshness::Fresh);
+    assert_eq!(Freshness::from_last_success(now, Some(now - Duration::minutes(20)), 300), Freshness::Stale);
+    assert_eq!(Freshness::from_last_success(now, Some(now - Duration::minutes(80)), 300), Freshness::Offline);
+}
+
+#[test]
+fn test_clock_is_berlin_time_with_dst() {
+    let config = StatusBarConfig::default();
+
+    // 12:00 UTC is 14:00 CEST in summer and 13:00 CET in winter
+    let summer = Utc.with_ymd_and_hms(2025, 7, 1, 12, 0, 0).unwrap().with_timezone(&TIMEZONE);
+    let winter = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap().with_timezone(&TIMEZONE);
+    assert_eq!(StatusLine::new(&config, &[], summer, None, 20).clock.as_deref(), Some("14:00"));
+    assert_eq!(StatusLine::new(&config, &[], winter, None, 20).clock.as_deref(), Some("13:00"));
+}
+
+#[test]
+fn test_status_line_parts_follow_config() {
+    let config = StatusBarConfig {
+        clock: false,
+        age: false,
+        station: Some("Warschauer Str.".to_string()),
+        ..StatusBarConfig::default()
+    };
+    let line = StatusLine::new(&config, &[], Utc::now().with_timezone(&TIMEZONE), None, 20);
+    assert_eq!(line.clock, None);
+    assert_eq!(line.freshness, None);
+    assert_eq!(line.station.as_deref(), Some("Warschauer Str."));
+}
+
+#[test]
+fn test_status_station_defaults_to_stop_name() {
+    let now = Utc::now().with_timezone(&TIMEZONE);
+    let stops = [
+        StopConfig::new("900120003"),
+        StopConfig { name: Some("Warschauer Str.".to_string()), ..StopConfig::new("900120004") },
+    ];
+    let line = StatusLine::new(&StatusBarConfig::default(), &stops, now, None, 20);
+    assert_eq!(line.station.as_deref(), Some("Warschauer Str."));
+
+    // The config string overrides the stop names
+    let config = StatusBarConfig { station: Some("Home".to_string()), ..StatusBarConfig::default() };
+    assert_eq!(StatusLine::new(&config, &stops, now, None, 20).station.as_deref(), Some("Home"));
+    assert_eq!(StatusLine::new(&StatusBarConfig::default(), &[], now, None, 20).station, None);
+}
+
+#[test]
+fn test_status_bar_config() {
+    let config = Config::from_toml("[display.status_bar]\nposition = \"bottom\"\nstation = \"Warschauer\"").unwrap();
+    let bar = config.display.status_bar.unwrap();
+    assert_eq!(bar.position, StatusPosition::Bottom);
+    assert!(bar.clock);
+    assert!(Config::default().display.status_bar.is_none());
+}
+
+#[test]
+fn test_grid_reserve_row() {
+    let font = rusty_bvg::font::FontChoice::Font4x6.builtin_metrics().unwrap();
+    let grid = Grid::new(32, font);
+
+    let (bar_y, rest) = grid.reserve_row(true);
+    assert_eq!(bar_y, 5);
+    assert_eq!(rest.rows, 2);
+    assert_eq!(rest.baseline(0), 14);
+
+    let (bar_y, rest) = grid.reserve_row(false);
+    assert_eq!(bar_y, 23);
+    assert_eq!(rest.baseline(0), 5);
+}
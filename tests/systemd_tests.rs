@@ -0,0 +1,26 @@
+use chrono::TimeZone;
+use rusty_bvg::schedule::TIMEZONE;
+use rusty_bvg::systemd::{fetch_status, Systemd};
+use rusty_bvg::Departure;
+use std::error::Error;
+
+#[test]
+fn test_fetch_status_text() {
+    let now = TIMEZONE.with_ymd_and_hms(2025, 3, 10, 8, 15, 30).unwrap();
+
+    let ok: Result<Vec<Departure>, Box<dyn Error>> = Ok(vec![Departure::new("U1", "Uhlandstr.", 3)]);
+    assert_eq!(fetch_status(&ok, now), "1 departures, last fetch 08:15:30");
+
+    let failed: Result<Vec<Departure>, Box<dyn Error>> = Err("HTTP error: timeout".into());
+    assert_eq!(fetch_status(&failed, now), "API error at 08:15:30: HTTP error: timeout");
+}
+
+#[test]
+fn test_notify_without_systemd_is_noop() {
+    // No $NOTIFY_SOCKET in tests: nothing is sent and nothing fails
+    let mut systemd = Systemd::new();
+    systemd.ready();
+    systemd.status("testing");
+    systemd.watchdog();
+    systemd.stopping();
+}
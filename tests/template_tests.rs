@@ -0,0 +1,79 @@
+use chrono::{TimeZone, Utc};
+use rusty_bvg::template::{CharCount, FormatConfig, Template, Words};
+use rusty_bvg::{Config, Departure};
+
+fn render(template: &str, departure: &Departure) -> String {
+    Template::parse(template).unwrap().render(departure, &Words::default())
+}
+
+#[test]
+fn test_fields() {
+    let departure = Departure::new("S3", "Erkner", 2)
+        .with_delay(Some(3))
+        .with_platform(Some("4".to_string()))
+        .with_planned(Some(Utc.with_ymd_and_hms(2025, 3, 10, 7, 58, 0).unwrap()));
+
+    assert_eq!(render("{line} → {dest} {min}'", &departure), "S3 → Erkner 2'");
+    assert_eq!(render("{line} {dest} {due}", &departure), "S3 Erkner 2 min");
+    // Planned time is Berlin time, UTC+1 in March
+    assert_eq!(render("{planned} {delay} Gl. {platform}", &departure), "08:58 +3 Gl. 4");
+    assert_eq!(render("{{{line}}}", &departure), "{S3}");
+}
+
+#[test]
+fn test_missing_values_are_empty() {
+    let departure = Departure::new("U1", "Uhlandstr.", 5).with_delay(Some(0));
+    assert_eq!(render("[{delay}|{platform}|{planned}]", &departure), "[||]");
+    assert_eq!(render("{delay}", &departure.clone().with_delay(Some(-1))), "-1");
+}
+
+#[test]
+fn test_padding_and_truncation() {
+    let departure = Departure::new("M10", "Hauptbahnhof", 7);
+    assert_eq!(render("{line:4}|{dest:6}|{min:>3}", &departure), "M10 |Hauptb|  7");
+    assert_eq!(render("{dest:<14}|", &departure), "Hauptbahnhof  |");
+    // Padding counts characters, not bytes
+    assert_eq!(render("{dest:6}|", &Departure::new("S9", "Schöneweide", 3)), "Schöne|");
+}
+
+#[test]
+fn test_render_fitted_in_chars() {
+    let template = Template::parse("{line} {dest} {min}'").unwrap();
+    let departure = Departure::new("S9", "Schöneweide", 3);
+    let cut = |text: &str, width: u32| text.chars().take(width as usize).collect();
+    // Only the destination is shortened, "S9  3'" leaves 7 characters for it
+    assert_eq!(template.render_fitted(&departure, &Words::default(), 13, &CharCount, cut), "S9 Schönew 3'");
+    assert_eq!(template.render_fitted(&departure, &Words::default(), 40, &CharCount, cut), "S9 Schöneweide 3'");
+}
+
+#[test]
+fn test_now_at_zero_minutes() {
+    let departure = Departure::new("U1", "Uhlandstr.", 0);
+    assert_eq!(render("{line} {min}'", &departure), "U1 now'");
+    assert_eq!(render("{due}", &departure), "now");
+
+    let german = Words { now: "jetzt", minutes: "Min.", cancelled: "entfällt" };
+    let template = Template::parse("{due}").unwrap();
+    assert_eq!(template.render(&departure, &german), "jetzt");
+    assert_eq!(template.render(&Departure::new("U1", "Uhlandstr.", 4), &german), "4 Min.");
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(Template::parse("{line").is_err());
+    assert!(Template::parse("line}").is_err());
+    assert!(Template::parse("{track}").unwrap_err().contains("expected one of"));
+    assert!(Template::parse("{dest:wide}").is_err());
+}
+
+#[test]
+fn test_format_config() {
+    assert_eq!(FormatConfig::default().board_minutes.source(), "{min}'");
+
+    let config = Config::from_toml("[format]\ndeparture = \"{line}: {dest} ({min})\"").unwrap();
+    let departure = Departure::new("U1", "Uhlandstr.", 4);
+    assert_eq!(departure.format_with(&config.format.departure, &Words::default()), "U1: Uhlandstr. (4)");
+
+    let error = Config::from_toml("[format]\ndeparture = \"{track}\"").unwrap_err();
+    assert!(error.to_string().contains("Unknown field"));
+}
@@ -0,0 +1,73 @@
+use rusty_bvg::transition::{frame_count, Layer, Transition};
+use rusty_bvg::Config;
+
+#[test]
+fn test_frame_count() {
+    assert_eq!(frame_count(400, 30), 12);
+    // Too short for a single frame still gives one
+    assert_eq!(frame_count(10, 30), 1);
+    assert_eq!(frame_count(0, 30), 1);
+}
+
+#[test]
+fn test_slide_up_moves_both_pages() {
+    let start = Transition::SlideUp.frame(0.0, 64, 32);
+    assert_eq!(start.old, Some(Layer { y_offset: 0, fade: 255 }));
+    assert_eq!(start.new, Some(Layer { y_offset: 32, fade: 255 }));
+
+    let half = Transition::SlideUp.frame(0.5, 64, 32);
+    assert_eq!(half.old.unwrap().y_offset, -16);
+    assert_eq!(half.new.unwrap().y_offset, 16);
+
+    let end = Transition::SlideUp.frame(1.0, 64, 32);
+    assert_eq!(end.new.unwrap().y_offset, 0);
+}
+
+#[test]
+fn test_fade_out_then_in() {
+    let early = Transition::Fade.frame(0.25, 64, 32);
+    assert_eq!(early.new, None);
+    assert_eq!(early.old.unwrap().fade, 128);
+
+    let late = Transition::Fade.frame(0.75, 64, 32);
+    assert_eq!(late.old, None);
+    assert_eq!(late.new.unwrap().fade, 128);
+
+    assert_eq!(Transition::Fade.frame(1.0, 64, 32).new.unwrap().fade, 255);
+}
+
+#[test]
+fn test_wipe_blanks_columns() {
+    let first = Transition::Wipe.frame(0.25, 64, 32);
+    assert!(first.old.is_some() && first.new.is_none());
+    assert_eq!(first.blank_columns, Some((0, 32)));
+
+    let second = Transition::Wipe.frame(0.75, 64, 32);
+    assert!(second.old.is_none() && second.new.is_some());
+    assert_eq!(second.blank_columns, Some((32, 64)));
+}
+
+#[test]
+fn test_transition_config() {
+    let config = Config::from_toml(
+        r#"
+        [display]
+        transition_ms = 600
+        transition_fps = 20
+
+        [[display.pages]]
+        type = "clock"
+        transition = "slide_up"
+
+        [[display.pages]]
+        type = "board"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.display.transition_ms, 600);
+    assert_eq!(config.display.pages[0].transition, Transition::SlideUp);
+    assert_eq!(config.display.pages[1].transition, Transition::None);
+
+    assert!(Config::from_toml("[display]\ntransition_fps = 0").is_err());
+    assert!(Config::from_toml("[[display.pages]]\ntype = \"clock\"\ntransition = \"spin\"").is_err());
+}
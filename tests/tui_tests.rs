@@ -0,0 +1,99 @@
+use chrono::Utc;
+use rusty_bvg::locale::Locale;
+use rusty_bvg::tui::{LogBuffer, Product, TuiState};
+use rusty_bvg::{Departure, StopConfig};
+use std::error::Error;
+use std::io::Write;
+
+fn stops() -> Vec<StopConfig> {
+    vec![
+        StopConfig { name: Some("Warschauer Str.".to_string()), ..StopConfig::new("900120003") },
+        StopConfig::new("900100003"),
+    ]
+}
+
+fn board() -> Result<Vec<Departure>, Box<dyn Error>> {
+    Ok(vec![
+        Departure::new("U1", "Uhlandstr.", 2).with_stop("900120003"),
+        Departure::new("S7", "Potsdam Hbf", 4).with_stop("900100003"),
+        Departure::new("M10", "Hauptbahnhof", 6).with_stop("900120003"),
+    ])
+}
+
+fn lines(state: &TuiState) -> Vec<&str> {
+    state.visible().iter().map(|d| d.line.as_str()).collect()
+}
+
+#[test]
+fn test_products() {
+    assert_eq!(Product::for_line("U12"), Product::UBahn);
+    assert_eq!(Product::for_line("S41"), Product::SBahn);
+    assert_eq!(Product::for_line("M10"), Product::Tram);
+    assert_eq!(Product::for_line("SEV"), Product::Tram);
+}
+
+#[test]
+fn test_stop_switcher() {
+    let mut state = TuiState::new(stops(), Locale::En);
+    state.update(board(), Utc::now());
+    assert_eq!(state.stop_label(), "All stops");
+    assert_eq!(lines(&state), vec!["U1", "S7", "M10"]);
+
+    state.next_stop();
+    assert_eq!(state.stop_label(), "Warschauer Str.");
+    assert_eq!(lines(&state), vec!["U1", "M10"]);
+
+    state.next_stop();
+    assert_eq!(state.stop_label(), "900100003");
+    state.next_stop();
+    assert_eq!(state.stop_label(), "All stops");
+
+    state.previous_stop();
+    assert_eq!(state.stop_label(), "900100003");
+}
+
+#[test]
+fn test_single_stop_has_nothing_to_switch() {
+    let mut state = TuiState::new(vec![StopConfig::new("900120003")], Locale::En);
+    state.next_stop();
+    assert_eq!(state.stop_label(), "900120003");
+}
+
+#[test]
+fn test_filter_toggle() {
+    let mut state = TuiState::new(stops(), Locale::En);
+    state.update(board(), Utc::now());
+    state.toggle(Product::UBahn);
+    assert!(!state.is_shown(Product::UBahn));
+    assert_eq!(lines(&state), vec!["S7", "M10"]);
+    state.toggle(Product::UBahn);
+    assert_eq!(lines(&state), vec!["U1", "S7", "M10"]);
+}
+
+#[test]
+fn test_failed_fetch_keeps_board() {
+    let mut state = TuiState::new(stops(), Locale::En);
+    state.update(board(), Utc::now());
+    state.update(Err("HTTP error: timeout".into()), Utc::now());
+    assert_eq!(state.visible().len(), 3);
+    assert_eq!(state.last_error.as_deref(), Some("HTTP error: timeout"));
+
+    state.update(board(), Utc::now());
+    assert_eq!(state.last_error, None);
+}
+
+#[test]
+fn test_log_buffer() {
+    let logs = LogBuffer::default();
+    let mut writer = logs.clone();
+    writer.write_all(b"first\n").unwrap();
+    writer.write_all(b"second\n\n").unwrap();
+    assert_eq!(logs.last(5), vec!["first", "second"]);
+    assert_eq!(logs.last(1), vec!["second"]);
+
+    for i in 0..300 {
+        logs.push(&i.to_string());
+    }
+    assert_eq!(logs.last(1000).len(), 200);
+    assert_eq!(logs.last(1), vec!["299"]);
+}
@@ -0,0 +1,61 @@
+use rusty_bvg::api::with_urgency;
+use rusty_bvg::urgency::Urgency;
+use rusty_bvg::{Config, Departure, StopConfig};
+
+#[test]
+fn test_classify() {
+    // 5 minutes to walk, 2 minutes of slack count as "leave now"
+    assert_eq!(Urgency::classify(4, 5, 2), Urgency::TooLate);
+    assert_eq!(Urgency::classify(5, 5, 2), Urgency::LeaveNow);
+    assert_eq!(Urgency::classify(7, 5, 2), Urgency::LeaveNow);
+    assert_eq!(Urgency::classify(8, 5, 2), Urgency::Relaxed);
+}
+
+#[test]
+fn test_unreachable_departures_are_hidden() {
+    let stop = StopConfig { walking_time: Some(4), ..StopConfig::new("900120003") };
+    let departures = vec![
+        Departure::new("U1", "Uhlandstr.", 2),
+        Departure::new("S5", "Spandau", 5),
+        Departure::new("M10", "Hauptbahnhof", 12),
+    ];
+
+    let departures = with_urgency(departures, &stop);
+    let result: Vec<_> = departures.iter().map(|d| (d.line.as_str(), d.urgency)).collect();
+    assert_eq!(
+        result,
+        vec![("S5", Some(Urgency::LeaveNow)), ("M10", Some(Urgency::Relaxed))]
+    );
+}
+
+#[test]
+fn test_no_walking_time_keeps_everything() {
+    let departures = vec![Departure::new("U1", "Uhlandstr.", 1)];
+    let departures = with_urgency(departures, &StopConfig::new("900120003"));
+    assert_eq!(departures.len(), 1);
+    assert_eq!(departures[0].urgency, None);
+}
+
+#[test]
+fn test_stops_config() {
+    assert_eq!(Config::default().stops, vec![StopConfig::new("900120003")]);
+
+    let config = Config::from_toml(
+        r#"
+        [[stops]]
+        id = "900120003"
+        walking_time = 6
+
+        [[stops]]
+        id = "900120004"
+        walking_time = 3
+        leave_now = 1
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.stops.len(), 2);
+    assert_eq!(config.stops[0].leave_now, 2);
+    assert_eq!(config.stops[1].walking_time, Some(3));
+
+    assert!(Config::from_toml("stops = []").is_err());
+}